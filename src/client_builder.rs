@@ -1,12 +1,37 @@
+use std::time::Duration;
 use crate::client::Client;
-use crate::protocol::conn_params::ConnParamsBuilder;
+use crate::error::Error;
+use crate::protocol::conn_params::{
+    ConnParamsBuilder,
+    Host,
+    LoadBalanceHosts,
+    SslMode,
+    TargetSessionAttrs,
+};
 
 pub struct ClientBuilder<'a> {
    user: &'a str,
    password: Option<&'a str>,
-   host: (u8, u8, u8, u8),
+   host: &'a str,
+   hostaddr: Option<&'a str>,
    port: u32,
    database: &'a str,
+   connect_timeout: Option<Duration>,
+   read_timeout: Option<Duration>,
+   cancel_on_timeout: bool,
+   statement_timeout: Option<Duration>,
+   keepalives: bool,
+   keepalives_idle: Option<Duration>,
+   keepalives_interval: Option<Duration>,
+   keepalives_count: Option<u32>,
+   tcp_user_timeout: Option<Duration>,
+   ssl_mode: SslMode,
+   ssl_root_cert: Option<&'a str>,
+   ssl_cert: Option<&'a str>,
+   ssl_key: Option<&'a str>,
+   ssl_sni: bool,
+   target_session_attrs: TargetSessionAttrs,
+   load_balance_hosts: LoadBalanceHosts,
 }
 
 impl<'a> ClientBuilder<'a> {
@@ -14,9 +39,26 @@ impl<'a> ClientBuilder<'a> {
         ClientBuilder {
             user: "postgres",
             password: None,
-            host: (127, 0, 0, 1),
+            host: "127.0.0.1",
+            hostaddr: None,
             port: 5432,
             database: "postgres",
+            connect_timeout: None,
+            read_timeout: None,
+            cancel_on_timeout: true,
+            statement_timeout: None,
+            keepalives: true,
+            keepalives_idle: None,
+            keepalives_interval: None,
+            keepalives_count: None,
+            tcp_user_timeout: None,
+            ssl_mode: SslMode::default(),
+            ssl_root_cert: None,
+            ssl_cert: None,
+            ssl_key: None,
+            ssl_sni: true,
+            target_session_attrs: TargetSessionAttrs::default(),
+            load_balance_hosts: LoadBalanceHosts::default(),
         }
     }
 
@@ -30,8 +72,20 @@ impl<'a> ClientBuilder<'a> {
         self
     }
 
+    /// Host name, IPv4 or IPv6 address, or the directory of a Unix
+    /// domain socket when it starts with `/`. Several hosts separated by
+    /// commas are tried in turn, all on the same port.
     pub fn host(mut self, host: &'a str) -> ClientBuilder<'a> {
-        self.host = parse_ipv4(host).unwrap();
+        self.host = host;
+        self
+    }
+
+    /// IP address to connect to without resolving the host, which is
+    /// still used to verify the server certificate. Only one TCP host may
+    /// be given with it: a list of hosts or a socket directory is
+    /// rejected with `Error::Config`.
+    pub fn hostaddr(mut self, hostaddr: &'a str) -> ClientBuilder<'a> {
+        self.hostaddr = Some(hostaddr);
         self
     }
 
@@ -45,63 +99,277 @@ impl<'a> ClientBuilder<'a> {
         self
     }
 
-    pub fn connect(self) -> Client {
-        if self.password.is_some() {
-            let conn_params = ConnParamsBuilder::new()
-                .user(self.user)
-                .password(self.password.unwrap())
-                .host(self.host)
-                .port(self.port)
-                .database(self.database)
-                .build();
+    /// Longest wait for the TCP connection, rounded up to whole seconds.
+    pub fn connect_timeout(mut self, timeout: Duration) -> ClientBuilder<'a> {
+        self.connect_timeout = Some(timeout);
+        self
+    }
 
-            Client::connect(conn_params).unwrap()
-        } else {
-            let conn_params = ConnParamsBuilder::new()
-                .user(self.user)
-                .host(self.host)
-                .port(self.port)
-                .database(self.database)
-                .build();
+    /// Longest wait for an answer of the server before the running query
+    /// is cancelled and `Error::Timeout` is returned.
+    pub fn read_timeout(mut self, timeout: Duration) -> ClientBuilder<'a> {
+        self.read_timeout = Some(timeout);
+        self
+    }
 
-            Client::connect(conn_params).unwrap()
-        }
+    /// Whether a read timeout cancels the query and keeps the connection,
+    /// the default, or closes the connection right away. A connection
+    /// that cannot be brought back after the cancel is closed as well,
+    /// and then fails with `Error::Closed`.
+    pub fn cancel_on_timeout(mut self, cancel_on_timeout: bool) -> ClientBuilder<'a> {
+        self.cancel_on_timeout = cancel_on_timeout;
+        self
     }
-}
 
-pub fn parse_ipv4(ip_str: &str) -> Result<(u8, u8, u8, u8), &'static str> {
-    if ip_str.to_lowercase() == "localhost" {
-        return Ok((127, 0, 0, 1));
+    /// Sets the server-side `statement_timeout` of the session.
+    pub fn statement_timeout(mut self, timeout: Duration) -> ClientBuilder<'a> {
+        self.statement_timeout = Some(timeout);
+        self
+    }
+
+    /// Turns TCP keepalives off; they are on by default.
+    pub fn keepalives(mut self, keepalives: bool) -> ClientBuilder<'a> {
+        self.keepalives = keepalives;
+        self
+    }
+
+    /// Idle time before the first keepalive probe, rounded up to whole
+    /// seconds.
+    pub fn keepalives_idle(mut self, idle: Duration) -> ClientBuilder<'a> {
+        self.keepalives_idle = Some(idle);
+        self
     }
 
-    let parts: Vec<&str> = ip_str.split('.').collect();
-    
-    if parts.len() != 4 {
-        return Err("IPv4 address must have exactly 4 octets");
+    /// Time between unanswered keepalive probes, rounded up to whole
+    /// seconds.
+    pub fn keepalives_interval(mut self, interval: Duration) -> ClientBuilder<'a> {
+        self.keepalives_interval = Some(interval);
+        self
     }
 
-    let mut octets = [0u8; 4];
-    for (i, part) in parts.iter().enumerate() {
-        octets[i] = part.parse().map_err(|_| "Invalid octet value")?;
+    /// Unanswered keepalive probes after which the connection is dropped.
+    pub fn keepalives_count(mut self, count: u32) -> ClientBuilder<'a> {
+        self.keepalives_count = Some(count);
+        self
     }
 
-    Ok((octets[0], octets[1], octets[2], octets[3]))
+    /// How long sent data may stay unacknowledged before the connection
+    /// is dropped. Linux only.
+    pub fn tcp_user_timeout(mut self, timeout: Duration) -> ClientBuilder<'a> {
+        self.tcp_user_timeout = Some(timeout);
+        self
+    }
+
+    pub fn ssl_mode(mut self, ssl_mode: SslMode) -> ClientBuilder<'a> {
+        self.ssl_mode = ssl_mode;
+        self
+    }
+
+    /// PEM file with the CA certificates trusted to sign the server
+    /// certificate.
+    pub fn ssl_root_cert(mut self, path: &'a str) -> ClientBuilder<'a> {
+        self.ssl_root_cert = Some(path);
+        self
+    }
+
+    /// Client certificate and private key, both PEM files, for servers
+    /// that authenticate clients with certificates.
+    pub fn ssl_client_cert(mut self, cert: &'a str, key: &'a str) -> ClientBuilder<'a> {
+        self.ssl_cert = Some(cert);
+        self.ssl_key = Some(key);
+        self
+    }
+
+    /// Whether the host name is sent in the TLS handshake (SNI), the
+    /// default.
+    pub fn ssl_sni(mut self, ssl_sni: bool) -> ClientBuilder<'a> {
+        self.ssl_sni = ssl_sni;
+        self
+    }
+
+    pub fn target_session_attrs(mut self, target_session_attrs: TargetSessionAttrs) -> ClientBuilder<'a> {
+        self.target_session_attrs = target_session_attrs;
+        self
+    }
+
+    pub fn load_balance_hosts(mut self, load_balance_hosts: LoadBalanceHosts) -> ClientBuilder<'a> {
+        self.load_balance_hosts = load_balance_hosts;
+        self
+    }
+
+    pub fn connect(self) -> Result<Client, Error> {
+        let hosts = self.host.split(',')
+            .map(|host| Ok((Host::parse(host)?, self.port)))
+            .collect::<Result<Vec<_>, Error>>()?;
+        let hostaddr = self.hostaddr
+            .map(|addr| addr.parse()
+                .map_err(|_| Error::Config(format!("Invalid hostaddr '{}'", addr))))
+            .transpose()?;
+
+        let mut builder = ConnParamsBuilder::new();
+        let builder = builder
+            .user(self.user)
+            .hosts(hosts)
+            .hostaddr(hostaddr)
+            .database(self.database)
+            .connect_timeout(self.connect_timeout)
+            .read_timeout(self.read_timeout)
+            .cancel_on_timeout(self.cancel_on_timeout)
+            .statement_timeout(self.statement_timeout)
+            .keepalives(self.keepalives)
+            .keepalives_idle(self.keepalives_idle)
+            .keepalives_interval(self.keepalives_interval)
+            .keepalives_count(self.keepalives_count)
+            .tcp_user_timeout(self.tcp_user_timeout)
+            .ssl_mode(self.ssl_mode)
+            .ssl_root_cert(self.ssl_root_cert)
+            .ssl_cert(self.ssl_cert)
+            .ssl_key(self.ssl_key)
+            .ssl_sni(self.ssl_sni)
+            .target_session_attrs(self.target_session_attrs)
+            .load_balance_hosts(self.load_balance_hosts);
+        let conn_params = match self.password {
+            Some(password) => builder.password(password).build(),
+            None => builder.build(),
+        };
+
+        Client::connect(conn_params)
+    }
+}
+
+impl Default for ClientBuilder<'_> {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 #[cfg(test)]
 mod tests {
+    use std::time::Instant;
     use super::*;
-    use std::io::Result;
+    use crate::sql_state::SqlState;
 
     #[test]
-    fn test_client_connection() -> Result<()> {
+    fn test_client_connection() -> Result<(), Error> {
         ClientBuilder::new()
           .user("postgres")
           .host("localhost")
           .port(5432)
           .database("postgres")
-          .connect(); 
+          .connect()?;
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_timeouts() -> Result<(), Error> {
+        let mut client = ClientBuilder::new()
+            .password("test")
+            .connect_timeout(Duration::from_secs(1))
+            .read_timeout(Duration::from_millis(300))
+            .connect()?;
+
+        let started = Instant::now();
+        assert!(matches!(client.execute("SELECT pg_sleep(5);"), Err(Error::Timeout(_))));
+        assert!(started.elapsed() < Duration::from_secs(2));
+        assert_eq!(client.query("SELECT 1::int4;")?[0].get::<i32>(0)?, 1);
+
+        let mut client = ClientBuilder::new()
+            .password("test")
+            .read_timeout(Duration::from_millis(300))
+            .cancel_on_timeout(false)
+            .connect()?;
+
+        let started = Instant::now();
+        assert!(matches!(client.execute("SELECT pg_sleep(5);"), Err(Error::Timeout(_))));
+        assert!(started.elapsed() < Duration::from_secs(1));
+        assert!(matches!(client.execute("SELECT 1;"), Err(Error::Closed)));
+
+        let mut client = ClientBuilder::new()
+            .password("test")
+            .statement_timeout(Duration::from_millis(100))
+            .connect()?;
+
+        let err = client.execute("SELECT pg_sleep(5);").unwrap_err();
+        assert_eq!(err.code(), Some(&SqlState::QUERY_CANCELED));
+        client.execute("SELECT 1;")?;
+
+        client.execute("RESET ALL;")?;
+        let rows = client.query("SHOW statement_timeout;")?;
+        assert_eq!(rows[0].get::<String>(0)?, "100ms");
+        Ok(())
+    }
+
+    #[test]
+    fn test_unix_socket() -> Result<(), Error> {
+        let mut client = ClientBuilder::new().connect()?;
+        let dirs = client.query("SHOW unix_socket_directories;")?[0].get::<String>(0)?;
+        let dir = dirs.split(',').next().unwrap().trim().to_string();
+
+        let mut client = ClientBuilder::new()
+            .host(&dir)
+            .connect()?;
+        let rows = client.query("SELECT inet_server_addr() IS NULL;")?;
+        assert!(rows[0].get::<bool>(0)?);
+
+        let mut client = Client::connect_from_dsn(&format!("postgresql:///postgres?host={}", dir))?;
+        let rows = client.query("SELECT inet_server_addr() IS NULL;")?;
+        assert!(rows[0].get::<bool>(0)?);
 
+        let result = ClientBuilder::new()
+            .host("/nonexistent")
+            .connect();
+        assert!(matches!(result, Err(Error::Io(_))));
         Ok(())
     }
+
+    #[test]
+    fn test_host_name() -> Result<(), Error> {
+        let mut client = ClientBuilder::new()
+            .host("localhost")
+            .connect()?;
+        assert_eq!(client.conn_params().host(), &Host::Name("localhost".to_string()));
+        client.execute("SELECT 1;")?;
+
+        ClientBuilder::new()
+            .host("db.invalid")
+            .hostaddr("127.0.0.1")
+            .connect()?;
+
+        let result = ClientBuilder::new()
+            .hostaddr("db.invalid")
+            .connect();
+        assert!(matches!(result, Err(Error::Config(_))));
+
+        let result = ClientBuilder::new()
+            .host("127.0.0.1,localhost")
+            .hostaddr("127.0.0.1")
+            .connect();
+        assert!(matches!(result, Err(Error::Config(_))));
+
+        let result = ClientBuilder::new()
+            .host("/tmp")
+            .hostaddr("127.0.0.1")
+            .connect();
+        assert!(matches!(result, Err(Error::Config(_))));
+
+        let conn_params = ConnParamsBuilder::new()
+            .hosts(vec![
+                (Host::Name("db.invalid".to_string()), 5432),
+                (Host::Name("localhost".to_string()), 5432),
+            ])
+            .hostaddr(Some("127.0.0.1".parse().unwrap()))
+            .build();
+        assert!(matches!(Client::connect(conn_params), Err(Error::Config(_))));
+        Ok(())
+    }
+
+    #[test]
+    fn test_client_invalid_host() {
+        let result = ClientBuilder::new()
+          .host("not an address")
+          .connect();
+
+        assert!(matches!(result, Err(Error::Config(_))));
+    }
 }
@@ -0,0 +1,268 @@
+use std::fmt;
+use crate::sql_state::SqlState;
+
+/// Fields of an ErrorResponse or NoticeResponse message.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DbError {
+    severity: String,
+    severity_nonlocalized: Option<String>,
+    code: SqlState,
+    message: String,
+    detail: Option<String>,
+    hint: Option<String>,
+    position: Option<u32>,
+    internal_position: Option<u32>,
+    internal_query: Option<String>,
+    where_: Option<String>,
+    schema: Option<String>,
+    table: Option<String>,
+    column: Option<String>,
+    datatype: Option<String>,
+    constraint: Option<String>,
+    file: Option<String>,
+    line: Option<u32>,
+    routine: Option<String>,
+}
+
+impl DbError {
+    /// Parses the body of an ErrorResponse or NoticeResponse: a list of
+    /// one-byte field tags, each followed by a null-terminated string.
+    pub fn parse(body: &[u8]) -> DbError {
+        let mut error = DbError {
+            severity: String::new(),
+            severity_nonlocalized: None,
+            code: SqlState::Other(String::new()),
+            message: String::new(),
+            detail: None,
+            hint: None,
+            position: None,
+            internal_position: None,
+            internal_query: None,
+            where_: None,
+            schema: None,
+            table: None,
+            column: None,
+            datatype: None,
+            constraint: None,
+            file: None,
+            line: None,
+            routine: None,
+        };
+
+        let mut rest = body;
+        while let Some((&tag, tail)) = rest.split_first() {
+            if tag == 0 {
+                break;
+            }
+
+            let end = tail.iter().position(|b| *b == 0).unwrap_or(tail.len());
+            let value = String::from_utf8_lossy(&tail[..end]).into_owned();
+            rest = tail.get(end + 1..).unwrap_or(&[]);
+
+            match tag {
+                b'S' => error.severity = value,
+                b'V' => error.severity_nonlocalized = Some(value),
+                b'C' => error.code = SqlState::from_code(&value),
+                b'M' => error.message = value,
+                b'D' => error.detail = Some(value),
+                b'H' => error.hint = Some(value),
+                b'P' => error.position = value.parse().ok(),
+                b'p' => error.internal_position = value.parse().ok(),
+                b'q' => error.internal_query = Some(value),
+                b'W' => error.where_ = Some(value),
+                b's' => error.schema = Some(value),
+                b't' => error.table = Some(value),
+                b'c' => error.column = Some(value),
+                b'd' => error.datatype = Some(value),
+                b'n' => error.constraint = Some(value),
+                b'F' => error.file = Some(value),
+                b'L' => error.line = value.parse().ok(),
+                b'R' => error.routine = Some(value),
+                // Unknown fields must be ignored, as future servers may add more.
+                _ => {},
+            }
+        }
+
+        error
+    }
+
+    /// Localized severity, e.g. `ERROR` or `NOTICE`.
+    #[inline]
+    pub fn severity(&self) -> &str {
+        &self.severity
+    }
+
+    /// Severity that is never localized (PostgreSQL 9.6+).
+    #[inline]
+    pub fn severity_nonlocalized(&self) -> Option<&str> {
+        self.severity_nonlocalized.as_deref()
+    }
+
+    #[inline]
+    pub fn code(&self) -> &SqlState {
+        &self.code
+    }
+
+    #[inline]
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+
+    #[inline]
+    pub fn detail(&self) -> Option<&str> {
+        self.detail.as_deref()
+    }
+
+    #[inline]
+    pub fn hint(&self) -> Option<&str> {
+        self.hint.as_deref()
+    }
+
+    /// 1-based character offset of the error in the query string.
+    #[inline]
+    pub fn position(&self) -> Option<u32> {
+        self.position
+    }
+
+    /// Like `position`, but for an internally generated query.
+    #[inline]
+    pub fn internal_position(&self) -> Option<u32> {
+        self.internal_position
+    }
+
+    #[inline]
+    pub fn internal_query(&self) -> Option<&str> {
+        self.internal_query.as_deref()
+    }
+
+    /// Call stack context, e.g. the PL/pgSQL function being executed.
+    #[inline]
+    pub fn where_(&self) -> Option<&str> {
+        self.where_.as_deref()
+    }
+
+    #[inline]
+    pub fn schema(&self) -> Option<&str> {
+        self.schema.as_deref()
+    }
+
+    #[inline]
+    pub fn table(&self) -> Option<&str> {
+        self.table.as_deref()
+    }
+
+    #[inline]
+    pub fn column(&self) -> Option<&str> {
+        self.column.as_deref()
+    }
+
+    #[inline]
+    pub fn datatype(&self) -> Option<&str> {
+        self.datatype.as_deref()
+    }
+
+    #[inline]
+    pub fn constraint(&self) -> Option<&str> {
+        self.constraint.as_deref()
+    }
+
+    /// Server source file that reported the error.
+    #[inline]
+    pub fn file(&self) -> Option<&str> {
+        self.file.as_deref()
+    }
+
+    #[inline]
+    pub fn line(&self) -> Option<u32> {
+        self.line
+    }
+
+    #[inline]
+    pub fn routine(&self) -> Option<&str> {
+        self.routine.as_deref()
+    }
+}
+
+impl fmt::Display for DbError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {} ({})", self.severity, self.message, self.code.code())?;
+
+        if let Some(detail) = &self.detail {
+            write!(f, "\nDETAIL: {}", detail)?;
+        }
+        if let Some(hint) = &self.hint {
+            write!(f, "\nHINT: {}", hint)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl std::error::Error for DbError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn field(body: &mut Vec<u8>, tag: u8, value: &str) {
+        body.push(tag);
+        body.extend(value.as_bytes());
+        body.push(0);
+    }
+
+    #[test]
+    fn test_parse() {
+        let mut body = Vec::new();
+        field(&mut body, b'S', "ОШИБКА");
+        field(&mut body, b'V', "ERROR");
+        field(&mut body, b'C', "23505");
+        field(&mut body, b'M', "duplicate key value violates unique constraint \"users_pkey\"");
+        field(&mut body, b'D', "Key (id)=(1) already exists.");
+        field(&mut body, b's', "public");
+        field(&mut body, b't', "users");
+        field(&mut body, b'n', "users_pkey");
+        field(&mut body, b'F', "nbtinsert.c");
+        field(&mut body, b'L', "664");
+        field(&mut body, b'R', "_bt_check_unique");
+        field(&mut body, b'Z', "unknown field");
+        body.push(0);
+
+        let error = DbError::parse(&body);
+
+        assert_eq!(error.severity(), "ОШИБКА");
+        assert_eq!(error.severity_nonlocalized(), Some("ERROR"));
+        assert_eq!(error.code(), &SqlState::UNIQUE_VIOLATION);
+        assert_eq!(error.detail(), Some("Key (id)=(1) already exists."));
+        assert_eq!(error.hint(), None);
+        assert_eq!(error.schema(), Some("public"));
+        assert_eq!(error.table(), Some("users"));
+        assert_eq!(error.constraint(), Some("users_pkey"));
+        assert_eq!(error.file(), Some("nbtinsert.c"));
+        assert_eq!(error.line(), Some(664));
+        assert_eq!(error.routine(), Some("_bt_check_unique"));
+    }
+
+    #[test]
+    fn test_parse_position() {
+        let mut body = Vec::new();
+        field(&mut body, b'S', "ERROR");
+        field(&mut body, b'C', "42601");
+        field(&mut body, b'M', "syntax error at or near \"SELEC\"");
+        field(&mut body, b'P', "1");
+        body.push(0);
+
+        let error = DbError::parse(&body);
+
+        assert_eq!(error.code(), &SqlState::SYNTAX_ERROR);
+        assert_eq!(error.position(), Some(1));
+        assert_eq!(error.to_string(), "ERROR: syntax error at or near \"SELEC\" (42601)");
+    }
+
+    #[test]
+    fn test_parse_truncated() {
+        let error = DbError::parse(b"SERROR\0Mno terminator");
+
+        assert_eq!(error.severity(), "ERROR");
+        assert_eq!(error.message(), "no terminator");
+    }
+}
@@ -0,0 +1,83 @@
+use std::fmt;
+use std::io;
+use crate::db_error::DbError;
+use crate::sql_state::SqlState;
+
+/// Errors returned by every fallible operation of the crate.
+#[derive(Debug)]
+pub enum Error {
+    /// Reading from or writing to the socket failed.
+    Io(io::Error),
+    /// The server sent something the client did not expect or understand.
+    Protocol(String),
+    /// Authentication could not be completed on the client side.
+    Authentication(String),
+    /// Connection parameters or a DSN are invalid.
+    Config(String),
+    /// The server answered with an ErrorResponse.
+    Server(Box<DbError>),
+    /// TLS could not be negotiated or the server certificate was rejected.
+    Tls(String),
+    /// No answer arrived within the allotted time.
+    Timeout(String),
+    /// An earlier error left the connection in an unknown state, so it
+    /// can no longer be used.
+    Closed,
+    /// A row has no column with the requested index or name.
+    Column(String),
+    /// A value could not be converted between Rust and PostgreSQL.
+    Conversion(String),
+    /// The operation is not valid in the current transaction state.
+    Transaction(String),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Io(err) => write!(f, "IO error: {}", err),
+            Error::Protocol(msg) => write!(f, "protocol error: {}", msg),
+            Error::Authentication(msg) => write!(f, "authentication error: {}", msg),
+            Error::Config(msg) => write!(f, "configuration error: {}", msg),
+            Error::Server(err) => write!(f, "server error: {}", err),
+            Error::Tls(msg) => write!(f, "TLS error: {}", msg),
+            Error::Timeout(msg) => write!(f, "timeout: {}", msg),
+            Error::Closed => write!(f, "connection closed after an earlier error"),
+            Error::Column(msg) => write!(f, "column error: {}", msg),
+            Error::Conversion(msg) => write!(f, "conversion error: {}", msg),
+            Error::Transaction(msg) => write!(f, "transaction error: {}", msg),
+        }
+    }
+}
+
+impl Error {
+    /// The server-reported error, if this is `Error::Server`.
+    pub fn as_db_error(&self) -> Option<&DbError> {
+        match self {
+            Error::Server(err) => Some(err),
+            _ => None,
+        }
+    }
+
+    /// SQLSTATE code of the server-reported error, if any.
+    pub fn code(&self) -> Option<&SqlState> {
+        self.as_db_error().map(DbError::code)
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Io(err) => Some(err),
+            Error::Server(err) => Some(err.as_ref()),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for Error {
+    fn from(err: io::Error) -> Error {
+        Error::Io(err)
+    }
+}
+
+pub type Result<T> = std::result::Result<T, Error>;
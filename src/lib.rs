@@ -2,3 +2,18 @@ pub mod protocol;
 pub mod messages;
 pub mod client;
 pub mod client_builder;
+pub mod row;
+pub mod statement;
+pub mod copy;
+pub mod binary_copy;
+pub mod notification;
+pub mod cancel;
+pub mod transaction;
+pub mod transaction_builder;
+pub mod error;
+pub mod db_error;
+pub mod sql_state;
+
+pub use error::Error;
+pub use db_error::DbError;
+pub use sql_state::SqlState;
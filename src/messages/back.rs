@@ -1,14 +1,38 @@
-use std::net::TcpStream;
-use std::io::{Error, Read};
-use std::io;
-use std::os::fd::AsRawFd;
-use libc::{poll, pollfd, POLLIN};
-use std::os::unix::io::RawFd;
+use std::io::Read;
+use super::types::{Format, TransactionStatus};
+use crate::error::Error;
+use crate::db_error::DbError;
+use crate::notification::Notification;
 
 pub enum BackTypes {
     AuthenticationOk,
+    AuthenticationCleartextPassword,
+    AuthenticationMD5Password,
+    AuthenticationSASL,
+    AuthenticationSASLContinue,
+    AuthenticationSASLFinal,
+    AuthenticationUnsupported,
     CommandComplete,
     ErrorResponse,
+    NoticeResponse,
+    ParameterStatus,
+    BackendKeyData,
+    ReadyForQuery,
+    RowDescription,
+    DataRow,
+    EmptyQueryResponse,
+    ParseComplete,
+    BindComplete,
+    CloseComplete,
+    ParameterDescription,
+    NoData,
+    PortalSuspended,
+    CopyInResponse,
+    CopyOutResponse,
+    CopyData,
+    CopyDone,
+    NotificationResponse,
+    Unknown,
 }
 
 pub struct BackMessage {
@@ -16,31 +40,132 @@ pub struct BackMessage {
     data: Vec<u8>,
 }
 
+/// One column of a `RowDescription`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FieldDescription {
+    name: String,
+    table_oid: u32,
+    column_id: i16,
+    type_oid: u32,
+    type_size: i16,
+    type_modifier: i32,
+    format: Format,
+}
+
+impl FieldDescription {
+    #[inline]
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    #[inline]
+    pub fn table_oid(&self) -> u32 {
+        self.table_oid
+    }
+
+    #[inline]
+    pub fn column_id(&self) -> i16 {
+        self.column_id
+    }
+
+    #[inline]
+    pub fn type_oid(&self) -> u32 {
+        self.type_oid
+    }
+
+    #[inline]
+    pub fn type_size(&self) -> i16 {
+        self.type_size
+    }
+
+    #[inline]
+    pub fn type_modifier(&self) -> i32 {
+        self.type_modifier
+    }
+
+    #[inline]
+    pub fn format(&self) -> Format {
+        self.format
+    }
+
+    /// An unnamed binary column, for values that come without a
+    /// RowDescription such as binary COPY data.
+    pub(crate) fn binary(type_oid: u32) -> FieldDescription {
+        FieldDescription {
+            name: String::new(),
+            table_oid: 0,
+            column_id: 0,
+            type_oid,
+            type_size: -1,
+            type_modifier: -1,
+            format: Format::Binary,
+        }
+    }
+
+    pub(crate) fn with_format(mut self, format: Format) -> FieldDescription {
+        self.format = format;
+        self
+    }
+}
+
 impl BackMessage {
-    pub fn from_buf(stream: &mut TcpStream) -> io::Result<BackMessage> {
+    /// Reads one message, blocking until all of it has arrived. Read
+    /// timeouts are up to `stream`.
+    pub fn from_buf<R: Read>(stream: &mut R) -> Result<BackMessage, Error> {
         let mut header = [0; 5];
 
-        wait_for_data(&stream.as_raw_fd(), 2000).unwrap();
-
         stream.read_exact(&mut header)?;
 
-        let msg_type = match header[0] {
-            b'R' => BackTypes::AuthenticationOk,
-            b'C' => BackTypes::CommandComplete,
-            _ => BackTypes::ErrorResponse,
-        };
-
         let msg_len = u32::from_be_bytes(
             [header[1], header[2], header[3], header[4]]
         ) as usize;
 
-        let mut body = vec![0; msg_len - 4];
-        stream.read_exact(&mut body)?;
+        if msg_len < 4 {
+            return Err(Error::Protocol(format!(
+                "Invalid length {} of backend message '{}'",
+                msg_len,
+                header[0] as char,
+            )));
+        }
+
+        // The length comes from the server, so the buffer only grows with
+        // the data that actually follows it.
+        let mut body = Vec::new();
+        stream.take(msg_len as u64 - 4).read_to_end(&mut body)?;
+        if body.len() != msg_len - 4 {
+            return Err(Error::Io(std::io::ErrorKind::UnexpectedEof.into()));
+        }
 
-        Ok(BackMessage {
-            msg_type: msg_type, 
-            data: body,
-        })
+        Ok(BackMessage::from_parts(header[0], body))
+    }
+
+    pub(crate) fn from_parts(tag: u8, data: Vec<u8>) -> BackMessage {
+        let msg_type = match tag {
+            b'R' => auth_type(&data),
+            b'C' => BackTypes::CommandComplete,
+            b'E' => BackTypes::ErrorResponse,
+            b'N' => BackTypes::NoticeResponse,
+            b'S' => BackTypes::ParameterStatus,
+            b'K' => BackTypes::BackendKeyData,
+            b'Z' => BackTypes::ReadyForQuery,
+            b'T' => BackTypes::RowDescription,
+            b'D' => BackTypes::DataRow,
+            b'I' => BackTypes::EmptyQueryResponse,
+            b'1' => BackTypes::ParseComplete,
+            b'2' => BackTypes::BindComplete,
+            b'3' => BackTypes::CloseComplete,
+            b't' => BackTypes::ParameterDescription,
+            b'n' => BackTypes::NoData,
+            b's' => BackTypes::PortalSuspended,
+            b'G' => BackTypes::CopyInResponse,
+            b'H' => BackTypes::CopyOutResponse,
+            b'd' => BackTypes::CopyData,
+            b'c' => BackTypes::CopyDone,
+            b'A' => BackTypes::NotificationResponse,
+            _ => BackTypes::Unknown,
+        };
+
+        BackMessage { msg_type, data }
     }
 
     #[inline]
@@ -50,24 +175,432 @@ impl BackMessage {
 
     #[inline]
     pub fn data(&self) -> String {
-        String::from_utf8(self.data.clone()).unwrap()
+        String::from_utf8_lossy(&self.data).into_owned()
+    }
+
+    /// Payload of an authentication request following the 4-byte
+    /// subtype code, e.g. the salt of `AuthenticationMD5Password`.
+    #[inline]
+    pub fn auth_data(&self) -> &[u8] {
+        self.data.get(4..).unwrap_or(&[])
+    }
+
+    /// Mechanism names listed by `AuthenticationSASL`.
+    pub fn sasl_mechanisms(&self) -> Vec<String> {
+        self.auth_data()
+            .split(|b| *b == 0)
+            .filter(|name| !name.is_empty())
+            .map(|name| String::from_utf8_lossy(name).into_owned())
+            .collect()
+    }
+
+    /// Name and value carried by `ParameterStatus`.
+    pub fn parameter_status(&self) -> Option<(String, String)> {
+        let mut parts = self.data.split(|b| *b == 0);
+        let name = parts.next()?;
+        let value = parts.next()?;
+
+        Some((
+            String::from_utf8_lossy(name).into_owned(),
+            String::from_utf8_lossy(value).into_owned(),
+        ))
+    }
+
+    /// Process ID and secret key carried by `BackendKeyData`.
+    pub fn backend_key_data(&self) -> Option<(i32, i32)> {
+        if self.data.len() < 8 {
+            return None;
+        }
+
+        Some((
+            i32::from_be_bytes([self.data[0], self.data[1], self.data[2], self.data[3]]),
+            i32::from_be_bytes([self.data[4], self.data[5], self.data[6], self.data[7]]),
+        ))
+    }
+
+    /// Column descriptions carried by `RowDescription`.
+    pub fn row_description(&self) -> Option<Vec<FieldDescription>> {
+        let mut cursor = Cursor::new(&self.data);
+        let count = cursor.read_i16()?;
+        let mut fields = Vec::with_capacity(count.max(0) as usize);
+
+        for _ in 0..count {
+            fields.push(FieldDescription {
+                name: cursor.read_cstr()?,
+                table_oid: cursor.read_i32()? as u32,
+                column_id: cursor.read_i16()?,
+                type_oid: cursor.read_i32()? as u32,
+                type_size: cursor.read_i16()?,
+                type_modifier: cursor.read_i32()?,
+                format: Format::from_code(cursor.read_i16()?),
+            });
+        }
+
+        Some(fields)
+    }
+
+    /// Column values carried by `DataRow`, with `None` for NULL.
+    pub fn data_row(&self) -> Option<Vec<Option<Vec<u8>>>> {
+        let mut cursor = Cursor::new(&self.data);
+        let count = cursor.read_i16()?;
+        let mut values = Vec::with_capacity(count.max(0) as usize);
+
+        for _ in 0..count {
+            let len = cursor.read_i32()?;
+            values.push(match len {
+                -1 => None,
+                len => Some(cursor.read_bytes(usize::try_from(len).ok()?)?.to_vec()),
+            });
+        }
+
+        Some(values)
+    }
+
+    /// Parameter type OIDs carried by `ParameterDescription`.
+    pub fn parameter_description(&self) -> Option<Vec<u32>> {
+        let mut cursor = Cursor::new(&self.data);
+        let count = cursor.read_i16()?;
+
+        (0..count)
+            .map(|_| cursor.read_i32().map(|oid| oid as u32))
+            .collect()
+    }
+
+    /// Command tag of `CommandComplete`, e.g. `INSERT 0 1` or `COPY 3`.
+    pub fn command_tag(&self) -> Option<String> {
+        Cursor::new(&self.data).read_cstr()
+    }
+
+    /// Overall format and per-column formats of a COPY response.
+    pub fn copy_response(&self) -> Option<(Format, Vec<Format>)> {
+        let mut cursor = Cursor::new(&self.data);
+        let format = Format::from_code(cursor.read_bytes(1)?[0] as i16);
+        let count = cursor.read_i16()?;
+
+        let columns = (0..count)
+            .map(|_| cursor.read_i16().map(Format::from_code))
+            .collect::<Option<Vec<Format>>>()?;
+
+        Some((format, columns))
+    }
+
+    /// Sender, channel and payload of a `NotificationResponse`.
+    pub fn notification(&self) -> Option<Notification> {
+        let mut cursor = Cursor::new(&self.data);
+        let process_id = cursor.read_i32()?;
+        let channel = cursor.read_cstr()?;
+        let payload = cursor.read_cstr()?;
+
+        Some(Notification::new(process_id, channel, payload))
+    }
+
+    /// Takes the payload of the message, e.g. the rows of `CopyData`.
+    pub fn into_data(self) -> Vec<u8> {
+        self.data
+    }
+
+    /// Transaction status carried by `ReadyForQuery`.
+    pub fn transaction_status(&self) -> Option<TransactionStatus> {
+        self.data.first().copied().and_then(TransactionStatus::from_code)
+    }
+
+    /// Fields of an `ErrorResponse` or `NoticeResponse`.
+    pub fn db_error(&self) -> DbError {
+        DbError::parse(&self.data)
+    }
+}
+
+struct Cursor<'a> {
+    buf: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Cursor<'a> {
+    fn new(buf: &'a [u8]) -> Cursor<'a> {
+        Cursor { buf, pos: 0 }
+    }
+
+    fn read_bytes(&mut self, len: usize) -> Option<&'a [u8]> {
+        let bytes = self.buf.get(self.pos..self.pos.checked_add(len)?)?;
+        self.pos += len;
+        Some(bytes)
+    }
+
+    fn read_i16(&mut self) -> Option<i16> {
+        let bytes = self.read_bytes(2)?;
+        Some(i16::from_be_bytes([bytes[0], bytes[1]]))
+    }
+
+    fn read_i32(&mut self) -> Option<i32> {
+        let bytes = self.read_bytes(4)?;
+        Some(i32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+    }
+
+    fn read_cstr(&mut self) -> Option<String> {
+        let rest = self.buf.get(self.pos..)?;
+        let end = rest.iter().position(|b| *b == 0)?;
+        self.pos += end + 1;
+        Some(String::from_utf8_lossy(&rest[..end]).into_owned())
+    }
+}
+
+fn auth_type(data: &[u8]) -> BackTypes {
+    if data.len() < 4 {
+        return BackTypes::AuthenticationUnsupported;
+    }
+
+    match i32::from_be_bytes([data[0], data[1], data[2], data[3]]) {
+        0 => BackTypes::AuthenticationOk,
+        3 => BackTypes::AuthenticationCleartextPassword,
+        5 => BackTypes::AuthenticationMD5Password,
+        10 => BackTypes::AuthenticationSASL,
+        11 => BackTypes::AuthenticationSASLContinue,
+        12 => BackTypes::AuthenticationSASLFinal,
+        _ => BackTypes::AuthenticationUnsupported,
     }
 }
 
-fn wait_for_data(fd: &RawFd, timeout_ms: i32) -> Result<(), String> {
-   let mut fds = [pollfd {
-        fd: *fd,
-        events: POLLIN,
-        revents: 0, 
-   }];
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn auth_body(code: i32, payload: &[u8]) -> Vec<u8> {
+        let mut body = code.to_be_bytes().to_vec();
+        body.extend(payload);
+        body
+    }
+
+    #[test]
+    fn test_from_buf() {
+        let mut data = &b"Z\0\0\0\x05I"[..];
+        let message = BackMessage::from_buf(&mut data).unwrap();
+        assert!(matches!(message.msg_type(), BackTypes::ReadyForQuery));
+
+        // A header claiming 2 GiB, followed by a single byte.
+        let mut data = &b"D\x7f\xff\xff\xff\0"[..];
+        assert!(matches!(BackMessage::from_buf(&mut data), Err(Error::Io(_))));
 
-   let ret = unsafe { poll(fds.as_mut_ptr(), 1, timeout_ms) };
+        let mut data = &b"Z\0\0\0\x03"[..];
+        assert!(matches!(BackMessage::from_buf(&mut data), Err(Error::Protocol(_))));
+    }
+
+    #[test]
+    fn test_auth_ok() {
+        let message = BackMessage::from_parts(b'R', auth_body(0, b""));
+
+        assert!(matches!(message.msg_type(), BackTypes::AuthenticationOk));
+        assert!(message.auth_data().is_empty());
+    }
+
+    #[test]
+    fn test_auth_cleartext() {
+        let message = BackMessage::from_parts(b'R', auth_body(3, b""));
+
+        assert!(matches!(
+            message.msg_type(),
+            BackTypes::AuthenticationCleartextPassword
+        ));
+    }
+
+    #[test]
+    fn test_auth_md5() {
+        let message = BackMessage::from_parts(b'R', auth_body(5, &[1, 2, 3, 4]));
+
+        assert!(matches!(message.msg_type(), BackTypes::AuthenticationMD5Password));
+        assert_eq!(message.auth_data(), &[1, 2, 3, 4]);
+    }
+
+    #[test]
+    fn test_auth_sasl() {
+        let message = BackMessage::from_parts(
+            b'R', auth_body(10, b"SCRAM-SHA-256-PLUS\0SCRAM-SHA-256\0\0"),
+        );
+
+        assert!(matches!(message.msg_type(), BackTypes::AuthenticationSASL));
+        assert_eq!(
+            message.sasl_mechanisms(),
+            vec!["SCRAM-SHA-256-PLUS".to_string(), "SCRAM-SHA-256".to_string()],
+        );
+    }
+
+    #[test]
+    fn test_auth_sasl_continue_and_final() {
+        let cont = BackMessage::from_parts(b'R', auth_body(11, b"r=abc,s=c2FsdA==,i=4096"));
+        let fin = BackMessage::from_parts(b'R', auth_body(12, b"v=c2lnbmF0dXJl"));
+
+        assert!(matches!(cont.msg_type(), BackTypes::AuthenticationSASLContinue));
+        assert_eq!(cont.auth_data(), b"r=abc,s=c2FsdA==,i=4096");
+        assert!(matches!(fin.msg_type(), BackTypes::AuthenticationSASLFinal));
+        assert_eq!(fin.auth_data(), b"v=c2lnbmF0dXJl");
+    }
+
+    #[test]
+    fn test_parameter_status() {
+        let mut body = b"server_version\0".to_vec();
+        body.extend(b"15.4\0");
+        let message = BackMessage::from_parts(b'S', body);
+
+        assert!(matches!(message.msg_type(), BackTypes::ParameterStatus));
+        assert_eq!(
+            message.parameter_status(),
+            Some(("server_version".to_string(), "15.4".to_string())),
+        );
+    }
 
-   if ret == -1 {
-       return Err(format!("ERROR: cannot wait for file descriptor {}", Error::last_os_error()));
-   } else if ret == 0 {
-       return Err("ERROR: time out during reading file descriptor".to_string());
-   }
+    #[test]
+    fn test_backend_key_data() {
+        let mut body = 1234_i32.to_be_bytes().to_vec();
+        body.extend(&(-42_i32).to_be_bytes());
+        let message = BackMessage::from_parts(b'K', body);
 
-   Ok(())
+        assert!(matches!(message.msg_type(), BackTypes::BackendKeyData));
+        assert_eq!(message.backend_key_data(), Some((1234, -42)));
+    }
+
+    #[test]
+    fn test_ready_for_query() {
+        let message = BackMessage::from_parts(b'Z', b"I".to_vec());
+
+        assert!(matches!(message.msg_type(), BackTypes::ReadyForQuery));
+        assert_eq!(message.transaction_status(), Some(TransactionStatus::Idle));
+        assert_eq!(
+            BackMessage::from_parts(b'Z', b"E".to_vec()).transaction_status(),
+            Some(TransactionStatus::Failed),
+        );
+        assert_eq!(BackMessage::from_parts(b'Z', vec![]).transaction_status(), None);
+    }
+
+    #[test]
+    fn test_row_description() {
+        let mut body = 2_i16.to_be_bytes().to_vec();
+        for (name, type_oid) in [("id", 23_i32), ("name", 25_i32)] {
+            body.extend(name.as_bytes());
+            body.push(0);
+            body.extend(&16384_i32.to_be_bytes());
+            body.extend(&1_i16.to_be_bytes());
+            body.extend(&type_oid.to_be_bytes());
+            body.extend(&4_i16.to_be_bytes());
+            body.extend(&(-1_i32).to_be_bytes());
+            body.extend(&0_i16.to_be_bytes());
+        }
+        let message = BackMessage::from_parts(b'T', body);
+
+        assert!(matches!(message.msg_type(), BackTypes::RowDescription));
+
+        let fields = message.row_description().unwrap();
+        assert_eq!(fields.len(), 2);
+        assert_eq!(fields[0].name(), "id");
+        assert_eq!(fields[0].table_oid(), 16384);
+        assert_eq!(fields[0].type_oid(), 23);
+        assert_eq!(fields[1].name(), "name");
+        assert_eq!(fields[1].type_oid(), 25);
+        assert_eq!(fields[1].format(), Format::Text);
+    }
+
+    #[test]
+    fn test_data_row() {
+        let mut body = 2_i16.to_be_bytes().to_vec();
+        body.extend(&1_i32.to_be_bytes());
+        body.extend(b"7");
+        body.extend(&(-1_i32).to_be_bytes());
+        let message = BackMessage::from_parts(b'D', body);
+
+        assert!(matches!(message.msg_type(), BackTypes::DataRow));
+        assert_eq!(message.data_row(), Some(vec![Some(b"7".to_vec()), None]));
+    }
+
+    #[test]
+    fn test_truncated_data_row() {
+        let mut body = 1_i16.to_be_bytes().to_vec();
+        body.extend(&10_i32.to_be_bytes());
+        body.extend(b"short");
+        let message = BackMessage::from_parts(b'D', body);
+
+        assert_eq!(message.data_row(), None);
+    }
+
+    #[test]
+    fn test_parameter_description() {
+        let mut body = 2_i16.to_be_bytes().to_vec();
+        body.extend(&23_i32.to_be_bytes());
+        body.extend(&25_i32.to_be_bytes());
+        let message = BackMessage::from_parts(b't', body);
+
+        assert!(matches!(message.msg_type(), BackTypes::ParameterDescription));
+        assert_eq!(message.parameter_description(), Some(vec![23, 25]));
+    }
+
+    #[test]
+    fn test_extended_query_responses() {
+        assert!(matches!(BackMessage::from_parts(b'1', vec![]).msg_type(), BackTypes::ParseComplete));
+        assert!(matches!(BackMessage::from_parts(b'2', vec![]).msg_type(), BackTypes::BindComplete));
+        assert!(matches!(BackMessage::from_parts(b'n', vec![]).msg_type(), BackTypes::NoData));
+    }
+
+    #[test]
+    fn test_notice_response() {
+        let message = BackMessage::from_parts(b'N', b"SNOTICE\0C00000\0Mhello\0\0".to_vec());
+
+        assert!(matches!(message.msg_type(), BackTypes::NoticeResponse));
+        assert_eq!(message.db_error().severity(), "NOTICE");
+        assert_eq!(message.db_error().message(), "hello");
+    }
+
+    #[test]
+    fn test_command_tag() {
+        let message = BackMessage::from_parts(b'C', b"COPY 3\0".to_vec());
+
+        assert!(matches!(message.msg_type(), BackTypes::CommandComplete));
+        assert_eq!(message.command_tag(), Some("COPY 3".to_string()));
+    }
+
+    #[test]
+    fn test_copy_in_response() {
+        let mut body = vec![0];
+        body.extend(&2_i16.to_be_bytes());
+        body.extend(&0_i16.to_be_bytes());
+        body.extend(&0_i16.to_be_bytes());
+        let message = BackMessage::from_parts(b'G', body);
+
+        assert!(matches!(message.msg_type(), BackTypes::CopyInResponse));
+        assert_eq!(
+            message.copy_response(),
+            Some((Format::Text, vec![Format::Text, Format::Text])),
+        );
+        assert_eq!(BackMessage::from_parts(b'G', vec![1, 0]).copy_response(), None);
+    }
+
+    #[test]
+    fn test_copy_out_messages() {
+        let message = BackMessage::from_parts(b'H', vec![1, 0, 1, 0, 1]);
+
+        assert!(matches!(message.msg_type(), BackTypes::CopyOutResponse));
+        assert_eq!(message.copy_response(), Some((Format::Binary, vec![Format::Binary])));
+
+        let message = BackMessage::from_parts(b'd', b"1\tone\n".to_vec());
+        assert!(matches!(message.msg_type(), BackTypes::CopyData));
+        assert_eq!(message.into_data(), b"1\tone\n");
+        assert!(matches!(BackMessage::from_parts(b'c', vec![]).msg_type(), BackTypes::CopyDone));
+    }
+
+    #[test]
+    fn test_notification_response() {
+        let mut body = 42_i32.to_be_bytes().to_vec();
+        body.extend(b"cache\0user:1\0");
+        let message = BackMessage::from_parts(b'A', body);
+
+        assert!(matches!(message.msg_type(), BackTypes::NotificationResponse));
+        let notification = message.notification().unwrap();
+        assert_eq!(notification.process_id(), 42);
+        assert_eq!(notification.channel(), "cache");
+        assert_eq!(notification.payload(), "user:1");
+        assert!(BackMessage::from_parts(b'A', vec![0, 0, 0, 1]).notification().is_none());
+    }
+
+    #[test]
+    fn test_auth_unsupported() {
+        let message = BackMessage::from_parts(b'R', auth_body(7, b""));
+
+        assert!(matches!(message.msg_type(), BackTypes::AuthenticationUnsupported));
+    }
 }
@@ -0,0 +1,243 @@
+use super::types::{oid, Format};
+use crate::error::Error;
+
+/// A Rust type that can be decoded from a column value.
+pub trait FromSql: Sized {
+    /// Whether values of the given type OID can be decoded into `Self`.
+    fn accepts(type_oid: u32) -> bool;
+
+    fn from_sql(type_oid: u32, format: Format, raw: &[u8]) -> Result<Self, Error>;
+
+    fn from_sql_null(_type_oid: u32) -> Result<Self, Error> {
+        Err(Error::Conversion("Unexpected NULL value".to_string()))
+    }
+
+    fn from_sql_nullable(
+        type_oid: u32, format: Format, raw: Option<&[u8]>
+    ) -> Result<Self, Error> {
+        match raw {
+            Some(raw) => Self::from_sql(type_oid, format, raw),
+            None => Self::from_sql_null(type_oid),
+        }
+    }
+}
+
+impl<T: FromSql> FromSql for Option<T> {
+    fn accepts(type_oid: u32) -> bool {
+        T::accepts(type_oid)
+    }
+
+    fn from_sql(type_oid: u32, format: Format, raw: &[u8]) -> Result<Self, Error> {
+        T::from_sql(type_oid, format, raw).map(Some)
+    }
+
+    fn from_sql_null(_type_oid: u32) -> Result<Self, Error> {
+        Ok(None)
+    }
+}
+
+impl FromSql for bool {
+    fn accepts(type_oid: u32) -> bool {
+        type_oid == oid::BOOL
+    }
+
+    fn from_sql(_type_oid: u32, format: Format, raw: &[u8]) -> Result<Self, Error> {
+        match (format, raw) {
+            (Format::Text, b"t") | (Format::Binary, [1]) => Ok(true),
+            (Format::Text, b"f") | (Format::Binary, [0]) => Ok(false),
+            _ => Err(Error::Conversion("Invalid bool value".to_string())),
+        }
+    }
+}
+
+macro_rules! from_sql_number {
+    ($t:ty, $oid:expr, $len:expr) => {
+        impl FromSql for $t {
+            fn accepts(type_oid: u32) -> bool {
+                type_oid == $oid
+            }
+
+            fn from_sql(_type_oid: u32, format: Format, raw: &[u8]) -> Result<Self, Error> {
+                match format {
+                    Format::Text => text(raw)?
+                        .parse()
+                        .map_err(|_| Error::Conversion(
+                            format!("Invalid {} value", stringify!($t)),
+                        )),
+                    Format::Binary => raw.try_into()
+                        .map(<$t>::from_be_bytes)
+                        .map_err(|_| Error::Conversion(format!(
+                            "Invalid {} value: expected {} bytes, got {}",
+                            stringify!($t), $len, raw.len(),
+                        ))),
+                }
+            }
+        }
+    };
+}
+
+from_sql_number!(i16, oid::INT2, 2);
+from_sql_number!(i32, oid::INT4, 4);
+from_sql_number!(i64, oid::INT8, 8);
+from_sql_number!(f32, oid::FLOAT4, 4);
+from_sql_number!(f64, oid::FLOAT8, 8);
+
+impl FromSql for String {
+    fn accepts(type_oid: u32) -> bool {
+        matches!(
+            type_oid,
+            oid::TEXT | oid::VARCHAR | oid::BPCHAR | oid::NAME | oid::UNKNOWN
+        )
+    }
+
+    fn from_sql(_type_oid: u32, _format: Format, raw: &[u8]) -> Result<Self, Error> {
+        text(raw).map(|value| value.to_string())
+    }
+}
+
+impl FromSql for Vec<u8> {
+    fn accepts(type_oid: u32) -> bool {
+        type_oid == oid::BYTEA
+    }
+
+    fn from_sql(_type_oid: u32, format: Format, raw: &[u8]) -> Result<Self, Error> {
+        match format {
+            Format::Binary => Ok(raw.to_vec()),
+            Format::Text => match raw.strip_prefix(b"\\x") {
+                Some(hex) => decode_hex(hex),
+                None => decode_escape(raw),
+            },
+        }
+    }
+}
+
+/// Format to request values of `type_oid` in: binary for the types
+/// decoded above, text for every other type so that `Row::get_str` can
+/// still read them.
+pub(crate) fn result_format(type_oid: u32) -> Format {
+    match type_oid {
+        oid::BOOL
+        | oid::BYTEA
+        | oid::INT2
+        | oid::INT4
+        | oid::INT8
+        | oid::FLOAT4
+        | oid::FLOAT8
+        | oid::TEXT
+        | oid::VARCHAR
+        | oid::BPCHAR
+        | oid::NAME => Format::Binary,
+        _ => Format::Text,
+    }
+}
+
+fn text(raw: &[u8]) -> Result<&str, Error> {
+    std::str::from_utf8(raw)
+        .map_err(|_| Error::Conversion("Column value is not valid UTF-8".to_string()))
+}
+
+fn decode_hex(hex: &[u8]) -> Result<Vec<u8>, Error> {
+    if !hex.len().is_multiple_of(2) {
+        return Err(Error::Conversion("Invalid bytea hex value".to_string()));
+    }
+
+    hex.chunks(2)
+        .map(|pair| {
+            std::str::from_utf8(pair)
+                .ok()
+                .and_then(|pair| u8::from_str_radix(pair, 16).ok())
+                .ok_or_else(|| Error::Conversion("Invalid bytea hex value".to_string()))
+        })
+        .collect()
+}
+
+// Legacy `bytea_output = 'escape'` format: `\\` for a backslash and
+// `\ooo` octal escapes for non-printable bytes.
+fn decode_escape(raw: &[u8]) -> Result<Vec<u8>, Error> {
+    let mut bytes = Vec::with_capacity(raw.len());
+    let mut i = 0;
+
+    while i < raw.len() {
+        if raw[i] != b'\\' {
+            bytes.push(raw[i]);
+            i += 1;
+        } else if raw.get(i + 1) == Some(&b'\\') {
+            bytes.push(b'\\');
+            i += 2;
+        } else {
+            let octal = raw.get(i + 1..i + 4)
+                .and_then(|digits| std::str::from_utf8(digits).ok())
+                .and_then(|digits| u8::from_str_radix(digits, 8).ok())
+                .ok_or_else(|| Error::Conversion("Invalid bytea escape value".to_string()))?;
+            bytes.push(octal);
+            i += 4;
+        }
+    }
+
+    Ok(bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bool() {
+        assert!(bool::from_sql(oid::BOOL, Format::Text, b"t").unwrap());
+        assert!(!bool::from_sql(oid::BOOL, Format::Binary, &[0]).unwrap());
+        assert!(bool::from_sql(oid::BOOL, Format::Text, b"yes").is_err());
+    }
+
+    #[test]
+    fn test_integers() {
+        assert_eq!(i16::from_sql(oid::INT2, Format::Text, b"-12").unwrap(), -12);
+        assert_eq!(i32::from_sql(oid::INT4, Format::Binary, &42_i32.to_be_bytes()).unwrap(), 42);
+        assert_eq!(i64::from_sql(oid::INT8, Format::Text, b"9000000000").unwrap(), 9_000_000_000);
+        assert!(i32::from_sql(oid::INT4, Format::Binary, &[0, 1]).is_err());
+        assert!(!i32::accepts(oid::INT8));
+    }
+
+    #[test]
+    fn test_floats() {
+        assert_eq!(f32::from_sql(oid::FLOAT4, Format::Binary, &1.5_f32.to_be_bytes()).unwrap(), 1.5);
+        assert_eq!(f64::from_sql(oid::FLOAT8, Format::Text, b"-2.25").unwrap(), -2.25);
+        assert!(f64::from_sql(oid::FLOAT8, Format::Text, b"NaN").unwrap().is_nan());
+        assert_eq!(f64::from_sql(oid::FLOAT8, Format::Text, b"-Infinity").unwrap(), f64::NEG_INFINITY);
+    }
+
+    #[test]
+    fn test_string() {
+        assert_eq!(String::from_sql(oid::TEXT, Format::Text, b"hello").unwrap(), "hello");
+        assert_eq!(String::from_sql(oid::VARCHAR, Format::Binary, b"hello").unwrap(), "hello");
+        assert!(String::from_sql(oid::TEXT, Format::Text, &[0xff]).is_err());
+    }
+
+    #[test]
+    fn test_bytea() {
+        assert_eq!(
+            Vec::<u8>::from_sql(oid::BYTEA, Format::Text, b"\\xdeadbeef").unwrap(),
+            vec![0xde, 0xad, 0xbe, 0xef],
+        );
+        assert_eq!(
+            Vec::<u8>::from_sql(oid::BYTEA, Format::Text, b"a\\\\b\\001").unwrap(),
+            vec![b'a', b'\\', b'b', 1],
+        );
+        assert_eq!(
+            Vec::<u8>::from_sql(oid::BYTEA, Format::Binary, &[0, 1, 2]).unwrap(),
+            vec![0, 1, 2],
+        );
+    }
+
+    #[test]
+    fn test_option() {
+        assert_eq!(
+            Option::<i32>::from_sql_nullable(oid::INT4, Format::Text, None).unwrap(),
+            None,
+        );
+        assert_eq!(
+            Option::<i32>::from_sql_nullable(oid::INT4, Format::Text, Some(b"5")).unwrap(),
+            Some(5),
+        );
+        assert!(i32::from_sql_nullable(oid::INT4, Format::Text, None).is_err());
+    }
+}
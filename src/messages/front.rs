@@ -1,6 +1,29 @@
+use super::types::Format;
+
 pub enum FrontTypes {
     StartupMessage,
     QueryMessage,
+    PasswordMessage,
+    SASLInitialResponse,
+    SASLResponse,
+    ParseMessage,
+    BindMessage,
+    DescribeMessage,
+    ExecuteMessage,
+    SyncMessage,
+    CloseMessage,
+    CopyDataMessage,
+    CopyDoneMessage,
+    CopyFailMessage,
+    CancelRequestMessage,
+    SSLRequestMessage,
+}
+
+/// What a Describe or Close message refers to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Target {
+    Statement,
+    Portal,
 }
 
 pub trait FrontendMessage {
@@ -10,6 +33,7 @@ pub trait FrontendMessage {
 pub struct StartupMessage {
     user: String,
     database: String,
+    params: Vec<(String, String)>,
     message: Vec<u8>
 }
 
@@ -18,9 +42,87 @@ pub struct QueryMessage {
     message: Vec<u8>,
 }
 
+pub struct PasswordMessage {
+    password: String,
+    message: Vec<u8>,
+}
+
+pub struct SASLInitialResponse {
+    mechanism: String,
+    data: Vec<u8>,
+    message: Vec<u8>,
+}
+
+pub struct SASLResponse {
+    data: Vec<u8>,
+    message: Vec<u8>,
+}
+
+pub struct ParseMessage {
+    name: String,
+    query: String,
+    param_types: Vec<u32>,
+    message: Vec<u8>,
+}
+
+pub struct BindMessage {
+    portal: String,
+    statement: String,
+    param_formats: Vec<Format>,
+    params: Vec<Option<Vec<u8>>>,
+    result_formats: Vec<Format>,
+    message: Vec<u8>,
+}
+
+pub struct DescribeMessage {
+    target: Target,
+    name: String,
+    message: Vec<u8>,
+}
+
+pub struct ExecuteMessage {
+    portal: String,
+    max_rows: i32,
+    message: Vec<u8>,
+}
+
+pub struct SyncMessage {
+    message: Vec<u8>,
+}
+
+pub struct CloseMessage {
+    target: Target,
+    name: String,
+    message: Vec<u8>,
+}
+
+pub struct CancelRequestMessage {
+    process_id: i32,
+    secret_key: i32,
+    message: Vec<u8>,
+}
+
+pub struct SSLRequestMessage {
+    message: Vec<u8>,
+}
+
+pub struct CopyDataMessage {
+    data: Vec<u8>,
+    message: Vec<u8>,
+}
+
+pub struct CopyDoneMessage {
+    message: Vec<u8>,
+}
+
+pub struct CopyFailMessage {
+    reason: String,
+    message: Vec<u8>,
+}
+
 impl StartupMessage {
     pub(in crate::messages) fn new(
-        user: &str, database: &str
+        user: &str, database: &str, params: &[(&str, &str)]
     ) -> StartupMessage {
         let mut message: Vec<u8> = Vec::new();
 
@@ -30,7 +132,10 @@ impl StartupMessage {
         StartupMessage {
             user: user.to_string(),
             database: database.to_string(),
-            message: message,
+            params: params.iter()
+                .map(|(name, value)| (name.to_string(), value.to_string()))
+                .collect(),
+            message,
         }
     }
 }
@@ -44,6 +149,12 @@ impl FrontendMessage for StartupMessage {
         add_bytes(&mut self.message, "database");
         add_bytes(&mut self.message, "\0");   
         add_bytes(&mut self.message, &self.database);
+        for (name, value) in &self.params {
+            add_bytes(&mut self.message, "\0");
+            add_bytes(&mut self.message, name);
+            add_bytes(&mut self.message, "\0");
+            add_bytes(&mut self.message, value);
+        }
         self.message.extend(b"\0\0");
           
         let len = self.message.len() as i32;
@@ -61,7 +172,7 @@ impl QueryMessage {
 
         QueryMessage { 
             query: query.to_string(),
-            message: message,
+            message,
         }
     }
 }
@@ -76,13 +187,391 @@ impl FrontendMessage for QueryMessage {
     }
 }
 
+impl PasswordMessage {
+    pub(in crate::messages) fn new(password: &str) -> PasswordMessage {
+        let mut message: Vec<u8> = Vec::new();
+
+        init_message(&mut message, FrontTypes::PasswordMessage);
+
+        PasswordMessage {
+            password: password.to_string(),
+            message,
+        }
+    }
+}
+
+impl FrontendMessage for PasswordMessage {
+    fn to_vecu8(&mut self) -> &Vec<u8> {
+        add_bytes(&mut self.message, &self.password);
+        self.message.extend(b"\0");
+
+        change_len(&mut self.message);
+        &self.message
+    }
+}
+
+impl SASLInitialResponse {
+    pub(in crate::messages) fn new(
+        mechanism: &str, data: &[u8]
+    ) -> SASLInitialResponse {
+        let mut message: Vec<u8> = Vec::new();
+
+        init_message(&mut message, FrontTypes::SASLInitialResponse);
+
+        SASLInitialResponse {
+            mechanism: mechanism.to_string(),
+            data: data.to_vec(),
+            message,
+        }
+    }
+}
+
+impl FrontendMessage for SASLInitialResponse {
+    fn to_vecu8(&mut self) -> &Vec<u8> {
+        add_bytes(&mut self.message, &self.mechanism);
+        self.message.extend(b"\0");
+        self.message.extend(&(self.data.len() as i32).to_be_bytes());
+        self.message.extend(&self.data);
+
+        change_len(&mut self.message);
+        &self.message
+    }
+}
+
+impl SASLResponse {
+    pub(in crate::messages) fn new(data: &[u8]) -> SASLResponse {
+        let mut message: Vec<u8> = Vec::new();
+
+        init_message(&mut message, FrontTypes::SASLResponse);
+
+        SASLResponse {
+            data: data.to_vec(),
+            message,
+        }
+    }
+}
+
+impl FrontendMessage for SASLResponse {
+    fn to_vecu8(&mut self) -> &Vec<u8> {
+        self.message.extend(&self.data);
+
+        change_len(&mut self.message);
+        &self.message
+    }
+}
+
+impl ParseMessage {
+    pub(in crate::messages) fn new(
+        name: &str, query: &str, param_types: &[u32]
+    ) -> ParseMessage {
+        let mut message: Vec<u8> = Vec::new();
+
+        init_message(&mut message, FrontTypes::ParseMessage);
+
+        ParseMessage {
+            name: name.to_string(),
+            query: query.to_string(),
+            param_types: param_types.to_vec(),
+            message,
+        }
+    }
+}
+
+impl FrontendMessage for ParseMessage {
+    fn to_vecu8(&mut self) -> &Vec<u8> {
+        add_bytes(&mut self.message, &self.name);
+        self.message.extend(b"\0");
+        add_bytes(&mut self.message, &self.query);
+        self.message.extend(b"\0");
+        self.message.extend(&(self.param_types.len() as i16).to_be_bytes());
+        for oid in &self.param_types {
+            self.message.extend(&oid.to_be_bytes());
+        }
+
+        change_len(&mut self.message);
+        &self.message
+    }
+}
+
+impl BindMessage {
+    pub(in crate::messages) fn new(
+        portal: &str,
+        statement: &str,
+        param_formats: &[Format],
+        params: &[Option<Vec<u8>>],
+        result_formats: &[Format],
+    ) -> BindMessage {
+        let mut message: Vec<u8> = Vec::new();
+
+        init_message(&mut message, FrontTypes::BindMessage);
+
+        BindMessage {
+            portal: portal.to_string(),
+            statement: statement.to_string(),
+            param_formats: param_formats.to_vec(),
+            params: params.to_vec(),
+            result_formats: result_formats.to_vec(),
+            message,
+        }
+    }
+}
+
+impl FrontendMessage for BindMessage {
+    fn to_vecu8(&mut self) -> &Vec<u8> {
+        add_bytes(&mut self.message, &self.portal);
+        self.message.extend(b"\0");
+        add_bytes(&mut self.message, &self.statement);
+        self.message.extend(b"\0");
+
+        self.message.extend(&(self.param_formats.len() as i16).to_be_bytes());
+        for format in &self.param_formats {
+            self.message.extend(&format.code().to_be_bytes());
+        }
+
+        self.message.extend(&(self.params.len() as i16).to_be_bytes());
+        for param in &self.params {
+            match param {
+                Some(value) => {
+                    self.message.extend(&(value.len() as i32).to_be_bytes());
+                    self.message.extend(value);
+                },
+                None => self.message.extend(&(-1_i32).to_be_bytes()),
+            }
+        }
+
+        self.message.extend(&(self.result_formats.len() as i16).to_be_bytes());
+        for format in &self.result_formats {
+            self.message.extend(&format.code().to_be_bytes());
+        }
+
+        change_len(&mut self.message);
+        &self.message
+    }
+}
+
+impl DescribeMessage {
+    pub(in crate::messages) fn new(target: Target, name: &str) -> DescribeMessage {
+        let mut message: Vec<u8> = Vec::new();
+
+        init_message(&mut message, FrontTypes::DescribeMessage);
+
+        DescribeMessage {
+            target,
+            name: name.to_string(),
+            message,
+        }
+    }
+}
+
+impl FrontendMessage for DescribeMessage {
+    fn to_vecu8(&mut self) -> &Vec<u8> {
+        self.message.push(target_byte(self.target));
+        add_bytes(&mut self.message, &self.name);
+        self.message.extend(b"\0");
+
+        change_len(&mut self.message);
+        &self.message
+    }
+}
+
+impl ExecuteMessage {
+    pub(in crate::messages) fn new(portal: &str, max_rows: i32) -> ExecuteMessage {
+        let mut message: Vec<u8> = Vec::new();
+
+        init_message(&mut message, FrontTypes::ExecuteMessage);
+
+        ExecuteMessage {
+            portal: portal.to_string(),
+            max_rows,
+            message,
+        }
+    }
+}
+
+impl FrontendMessage for ExecuteMessage {
+    fn to_vecu8(&mut self) -> &Vec<u8> {
+        add_bytes(&mut self.message, &self.portal);
+        self.message.extend(b"\0");
+        self.message.extend(&self.max_rows.to_be_bytes());
+
+        change_len(&mut self.message);
+        &self.message
+    }
+}
+
+impl SyncMessage {
+    pub(in crate::messages) fn new() -> SyncMessage {
+        let mut message: Vec<u8> = Vec::new();
+
+        init_message(&mut message, FrontTypes::SyncMessage);
+
+        SyncMessage { message }
+    }
+}
+
+impl FrontendMessage for SyncMessage {
+    fn to_vecu8(&mut self) -> &Vec<u8> {
+        change_len(&mut self.message);
+        &self.message
+    }
+}
+
+impl CloseMessage {
+    pub(in crate::messages) fn new(target: Target, name: &str) -> CloseMessage {
+        let mut message: Vec<u8> = Vec::new();
+
+        init_message(&mut message, FrontTypes::CloseMessage);
+
+        CloseMessage {
+            target,
+            name: name.to_string(),
+            message,
+        }
+    }
+}
+
+impl FrontendMessage for CloseMessage {
+    fn to_vecu8(&mut self) -> &Vec<u8> {
+        self.message.push(target_byte(self.target));
+        add_bytes(&mut self.message, &self.name);
+        self.message.extend(b"\0");
+
+        change_len(&mut self.message);
+        &self.message
+    }
+}
+
+impl CancelRequestMessage {
+    pub(in crate::messages) fn new(process_id: i32, secret_key: i32) -> CancelRequestMessage {
+        CancelRequestMessage {
+            process_id,
+            secret_key,
+            message: Vec::with_capacity(16),
+        }
+    }
+}
+
+impl FrontendMessage for CancelRequestMessage {
+    fn to_vecu8(&mut self) -> &Vec<u8> {
+        // Like StartupMessage, it has no type byte and is sent on a new
+        // connection instead of the one running the query.
+        self.message.clear();
+        self.message.extend(&16_i32.to_be_bytes());
+        self.message.extend(&80877102_i32.to_be_bytes());
+        self.message.extend(&self.process_id.to_be_bytes());
+        self.message.extend(&self.secret_key.to_be_bytes());
+
+        &self.message
+    }
+}
+
+impl SSLRequestMessage {
+    pub(in crate::messages) fn new() -> SSLRequestMessage {
+        let mut message = Vec::with_capacity(8);
+        message.extend(&8_i32.to_be_bytes());
+        message.extend(&80877103_i32.to_be_bytes());
+
+        SSLRequestMessage { message }
+    }
+}
+
+impl FrontendMessage for SSLRequestMessage {
+    fn to_vecu8(&mut self) -> &Vec<u8> {
+        // Sent before the StartupMessage, without a type byte.
+        &self.message
+    }
+}
+
+impl CopyDataMessage {
+    pub(in crate::messages) fn new(data: &[u8]) -> CopyDataMessage {
+        let mut message: Vec<u8> = Vec::with_capacity(data.len() + 5);
+
+        init_message(&mut message, FrontTypes::CopyDataMessage);
+
+        CopyDataMessage {
+            data: data.to_vec(),
+            message,
+        }
+    }
+}
+
+impl FrontendMessage for CopyDataMessage {
+    fn to_vecu8(&mut self) -> &Vec<u8> {
+        self.message.extend(&self.data);
+
+        change_len(&mut self.message);
+        &self.message
+    }
+}
+
+impl CopyDoneMessage {
+    pub(in crate::messages) fn new() -> CopyDoneMessage {
+        let mut message: Vec<u8> = Vec::new();
+
+        init_message(&mut message, FrontTypes::CopyDoneMessage);
+
+        CopyDoneMessage { message }
+    }
+}
+
+impl FrontendMessage for CopyDoneMessage {
+    fn to_vecu8(&mut self) -> &Vec<u8> {
+        change_len(&mut self.message);
+        &self.message
+    }
+}
+
+impl CopyFailMessage {
+    pub(in crate::messages) fn new(reason: &str) -> CopyFailMessage {
+        let mut message: Vec<u8> = Vec::new();
+
+        init_message(&mut message, FrontTypes::CopyFailMessage);
+
+        CopyFailMessage {
+            reason: reason.to_string(),
+            message,
+        }
+    }
+}
+
+impl FrontendMessage for CopyFailMessage {
+    fn to_vecu8(&mut self) -> &Vec<u8> {
+        add_bytes(&mut self.message, &self.reason);
+        self.message.extend(b"\0");
+
+        change_len(&mut self.message);
+        &self.message
+    }
+}
+
+fn target_byte(target: Target) -> u8 {
+    match target {
+        Target::Statement => b'S',
+        Target::Portal => b'P',
+    }
+}
+
 fn init_message(
     mess: &mut Vec<u8>, message_type: FrontTypes
 ) {
 
     mess.push(match message_type {
         FrontTypes::QueryMessage => b'Q',
-        _ => b'E',
+        FrontTypes::PasswordMessage
+            | FrontTypes::SASLInitialResponse
+            | FrontTypes::SASLResponse => b'p',
+        FrontTypes::ParseMessage => b'P',
+        FrontTypes::BindMessage => b'B',
+        FrontTypes::DescribeMessage => b'D',
+        FrontTypes::ExecuteMessage => b'E',
+        FrontTypes::SyncMessage => b'S',
+        FrontTypes::CloseMessage => b'C',
+        FrontTypes::CopyDataMessage => b'd',
+        FrontTypes::CopyDoneMessage => b'c',
+        FrontTypes::CopyFailMessage => b'f',
+        FrontTypes::StartupMessage => unreachable!("StartupMessage has no type byte"),
+        FrontTypes::CancelRequestMessage => unreachable!("CancelRequest has no type byte"),
+        FrontTypes::SSLRequestMessage => unreachable!("SSLRequest has no type byte"),
     });
     mess.extend(&0_i32.to_be_bytes());
 }
@@ -91,7 +580,7 @@ fn add_bytes(mess: &mut Vec<u8>, value: &str) {
     mess.extend(value.as_bytes());
 }
 
-fn change_len(mess: &mut Vec<u8>) {
+fn change_len(mess: &mut [u8]) {
     let len = (mess.len() - 1) as i32;
     mess[1..5].copy_from_slice(&len.to_be_bytes());
 }
@@ -119,9 +608,276 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_password_to_vecu8() {
+        let mut test_message = PasswordMessage::new("test");
+
+        let mut expected_message: Vec<u8> = Vec::new();
+
+        expected_message.push(b'p');
+        expected_message.extend(
+            &9_i32.to_be_bytes()
+        );
+        expected_message.extend(b"test\0");
+
+        assert_eq!(
+            *test_message.to_vecu8(),
+            expected_message
+        );
+    }
+
+    #[test]
+    fn test_sasl_initial_response_to_vecu8() {
+        let mut test_message = SASLInitialResponse::new("SCRAM-SHA-256", b"n,,n=,r=abc");
+
+        let mut expected_message: Vec<u8> = Vec::new();
+
+        expected_message.push(b'p');
+        expected_message.extend(
+            &33_i32.to_be_bytes()
+        );
+        expected_message.extend(b"SCRAM-SHA-256\0");
+        expected_message.extend(
+            &11_i32.to_be_bytes()
+        );
+        expected_message.extend(b"n,,n=,r=abc");
+
+        assert_eq!(
+            *test_message.to_vecu8(),
+            expected_message
+        );
+    }
+
+    #[test]
+    fn test_sasl_response_to_vecu8() {
+        let mut test_message = SASLResponse::new(b"c=biws");
+
+        let mut expected_message: Vec<u8> = Vec::new();
+
+        expected_message.push(b'p');
+        expected_message.extend(
+            &10_i32.to_be_bytes()
+        );
+        expected_message.extend(b"c=biws");
+
+        assert_eq!(
+            *test_message.to_vecu8(),
+            expected_message
+        );
+    }
+
+    #[test]
+    fn test_parse_to_vecu8() {
+        let mut test_message = ParseMessage::new("stmt", "SELECT $1", &[23]);
+
+        let mut expected_message: Vec<u8> = Vec::new();
+
+        expected_message.push(b'P');
+        expected_message.extend(
+            &25_i32.to_be_bytes()
+        );
+        expected_message.extend(b"stmt\0SELECT $1\0");
+        expected_message.extend(&1_i16.to_be_bytes());
+        expected_message.extend(&23_u32.to_be_bytes());
+
+        assert_eq!(
+            *test_message.to_vecu8(),
+            expected_message
+        );
+    }
+
+    #[test]
+    fn test_bind_to_vecu8() {
+        let mut test_message = BindMessage::new(
+            "",
+            "stmt",
+            &[Format::Text],
+            &[Some(b"42".to_vec()), None],
+            &[Format::Binary],
+        );
+
+        let mut expected_message: Vec<u8> = Vec::new();
+
+        expected_message.push(b'B');
+        expected_message.extend(
+            &30_i32.to_be_bytes()
+        );
+        expected_message.extend(b"\0stmt\0");
+        expected_message.extend(&1_i16.to_be_bytes());
+        expected_message.extend(&0_i16.to_be_bytes());
+        expected_message.extend(&2_i16.to_be_bytes());
+        expected_message.extend(&2_i32.to_be_bytes());
+        expected_message.extend(b"42");
+        expected_message.extend(&(-1_i32).to_be_bytes());
+        expected_message.extend(&1_i16.to_be_bytes());
+        expected_message.extend(&1_i16.to_be_bytes());
+
+        assert_eq!(
+            *test_message.to_vecu8(),
+            expected_message
+        );
+    }
+
+    #[test]
+    fn test_describe_to_vecu8() {
+        let mut test_message = DescribeMessage::new(Target::Portal, "");
+
+        let mut expected_message: Vec<u8> = Vec::new();
+
+        expected_message.push(b'D');
+        expected_message.extend(
+            &6_i32.to_be_bytes()
+        );
+        expected_message.extend(b"P\0");
+
+        assert_eq!(
+            *test_message.to_vecu8(),
+            expected_message
+        );
+    }
+
+    #[test]
+    fn test_execute_to_vecu8() {
+        let mut test_message = ExecuteMessage::new("", 0);
+
+        let mut expected_message: Vec<u8> = Vec::new();
+
+        expected_message.push(b'E');
+        expected_message.extend(
+            &9_i32.to_be_bytes()
+        );
+        expected_message.extend(b"\0");
+        expected_message.extend(&0_i32.to_be_bytes());
+
+        assert_eq!(
+            *test_message.to_vecu8(),
+            expected_message
+        );
+    }
+
+    #[test]
+    fn test_sync_to_vecu8() {
+        let mut test_message = SyncMessage::new();
+
+        let mut expected_message: Vec<u8> = Vec::new();
+
+        expected_message.push(b'S');
+        expected_message.extend(
+            &4_i32.to_be_bytes()
+        );
+
+        assert_eq!(
+            *test_message.to_vecu8(),
+            expected_message
+        );
+    }
+
+    #[test]
+    fn test_close_to_vecu8() {
+        let mut test_message = CloseMessage::new(Target::Statement, "stmt");
+
+        let mut expected_message: Vec<u8> = Vec::new();
+
+        expected_message.push(b'C');
+        expected_message.extend(
+            &10_i32.to_be_bytes()
+        );
+        expected_message.extend(b"Sstmt\0");
+
+        assert_eq!(
+            *test_message.to_vecu8(),
+            expected_message
+        );
+    }
+
+    #[test]
+    fn test_copy_data_to_vecu8() {
+        let mut test_message = CopyDataMessage::new(b"1\tone\n");
+
+        let mut expected_message: Vec<u8> = Vec::new();
+
+        expected_message.push(b'd');
+        expected_message.extend(
+            &10_i32.to_be_bytes()
+        );
+        expected_message.extend(b"1\tone\n");
+
+        assert_eq!(
+            *test_message.to_vecu8(),
+            expected_message
+        );
+    }
+
+    #[test]
+    fn test_copy_done_to_vecu8() {
+        let mut test_message = CopyDoneMessage::new();
+
+        let mut expected_message: Vec<u8> = Vec::new();
+
+        expected_message.push(b'c');
+        expected_message.extend(
+            &4_i32.to_be_bytes()
+        );
+
+        assert_eq!(
+            *test_message.to_vecu8(),
+            expected_message
+        );
+    }
+
+    #[test]
+    fn test_copy_fail_to_vecu8() {
+        let mut test_message = CopyFailMessage::new("oops");
+
+        let mut expected_message: Vec<u8> = Vec::new();
+
+        expected_message.push(b'f');
+        expected_message.extend(
+            &9_i32.to_be_bytes()
+        );
+        expected_message.extend(b"oops\0");
+
+        assert_eq!(
+            *test_message.to_vecu8(),
+            expected_message
+        );
+    }
+
+    #[test]
+    fn test_ssl_request_to_vecu8() {
+        let mut test_message = SSLRequestMessage::new();
+
+        let mut expected_message: Vec<u8> = Vec::new();
+
+        expected_message.extend(&8_i32.to_be_bytes());
+        expected_message.extend(&80877103_i32.to_be_bytes());
+
+        assert_eq!(
+            *test_message.to_vecu8(),
+            expected_message
+        );
+    }
+
+    #[test]
+    fn test_cancel_request_to_vecu8() {
+        let mut test_message = CancelRequestMessage::new(1234, -5);
+
+        let mut expected_message: Vec<u8> = Vec::new();
+
+        expected_message.extend(&16_i32.to_be_bytes());
+        expected_message.extend(&80877102_i32.to_be_bytes());
+        expected_message.extend(&1234_i32.to_be_bytes());
+        expected_message.extend(&(-5_i32).to_be_bytes());
+
+        assert_eq!(
+            *test_message.to_vecu8(),
+            expected_message
+        );
+    }
+
     #[test]
     fn test_startup_to_vecu8() {
-        let mut test_message = StartupMessage::new("postgres", "postgres");
+        let mut test_message = StartupMessage::new("postgres", "postgres", &[]);
 
         let mut expected_message: Vec<u8> = Vec::new();
 
@@ -140,6 +896,26 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_startup_with_params_to_vecu8() {
+        let mut test_message = StartupMessage::new(
+            "postgres", "postgres", &[("application_name", "app")],
+        );
+
+        let mut expected_message: Vec<u8> = Vec::new();
+
+        expected_message.extend(&62_i32.to_be_bytes());
+        expected_message.extend(&196608_i32.to_be_bytes());
+        expected_message.extend(
+            b"user\0postgres\0database\0postgres\0application_name\0app\0\0"
+        );
+
+        assert_eq!(
+            *test_message.to_vecu8(),
+            expected_message
+        );
+    }
+
     #[test]
     fn test_init_message() {
         let mut test_message: Vec<u8> = Vec::new();
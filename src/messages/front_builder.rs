@@ -1,22 +1,101 @@
 use super::front::{
     StartupMessage,
     QueryMessage,
+    PasswordMessage,
+    SASLInitialResponse,
+    SASLResponse,
+    ParseMessage,
+    BindMessage,
+    DescribeMessage,
+    ExecuteMessage,
+    SyncMessage,
+    CloseMessage,
+    CopyDataMessage,
+    CopyDoneMessage,
+    CopyFailMessage,
+    CancelRequestMessage,
+    SSLRequestMessage,
+    Target,
 };
+use super::types::Format;
 
 pub struct StartupMessageBuilder<'a> {
     user: &'a str,
     database: &'a str,
+    params: Vec<(&'a str, &'a str)>,
 }
 
 pub struct QueryMessageBuilder<'a> {
     query: &'a str,
 }
 
+pub struct PasswordMessageBuilder<'a> {
+    password: &'a str,
+}
+
+pub struct SASLInitialResponseBuilder<'a> {
+    mechanism: &'a str,
+    data: &'a [u8],
+}
+
+pub struct SASLResponseBuilder<'a> {
+    data: &'a [u8],
+}
+
+pub struct ParseMessageBuilder<'a> {
+    name: &'a str,
+    query: &'a str,
+    param_types: &'a [u32],
+}
+
+pub struct BindMessageBuilder<'a> {
+    portal: &'a str,
+    statement: &'a str,
+    param_formats: &'a [Format],
+    params: &'a [Option<Vec<u8>>],
+    result_formats: &'a [Format],
+}
+
+pub struct DescribeMessageBuilder<'a> {
+    target: Target,
+    name: &'a str,
+}
+
+pub struct ExecuteMessageBuilder<'a> {
+    portal: &'a str,
+    max_rows: i32,
+}
+
+pub struct SyncMessageBuilder;
+
+pub struct CancelRequestMessageBuilder {
+    process_id: i32,
+    secret_key: i32,
+}
+
+pub struct SSLRequestMessageBuilder;
+
+pub struct CopyDataMessageBuilder<'a> {
+    data: &'a [u8],
+}
+
+pub struct CopyDoneMessageBuilder;
+
+pub struct CopyFailMessageBuilder<'a> {
+    reason: &'a str,
+}
+
+pub struct CloseMessageBuilder<'a> {
+    target: Target,
+    name: &'a str,
+}
+
 impl<'a> StartupMessageBuilder<'a> {
     pub fn new() -> StartupMessageBuilder<'a> {
         StartupMessageBuilder { 
             user: "postgres", 
             database: "postgres",
+            params: Vec::new(),
         }
     }
 
@@ -30,8 +109,21 @@ impl<'a> StartupMessageBuilder<'a> {
         self
     }
 
+    /// Adds a run-time parameter such as `application_name`, set for the
+    /// session as if with `SET`.
+    pub fn param(mut self, name: &'a str, value: &'a str) -> StartupMessageBuilder<'a> {
+        self.params.push((name, value));
+        self
+    }
+
     pub fn build(self) -> StartupMessage {
-        StartupMessage::new(self.user, self.database)
+        StartupMessage::new(self.user, self.database, &self.params)
+    }
+}
+
+impl Default for StartupMessageBuilder<'_> {
+    fn default() -> Self {
+        Self::new()
     }
 }
 
@@ -53,3 +145,398 @@ impl<'a> QueryMessageBuilder<'a> {
         QueryMessage::new(self.query)
     }
 }
+
+impl Default for QueryMessageBuilder<'_> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<'a> PasswordMessageBuilder<'a> {
+    pub fn new() -> PasswordMessageBuilder<'a> {
+        PasswordMessageBuilder {
+            password: "",
+        }
+    }
+
+    pub fn password(
+        mut self, password: &'a str
+    ) -> PasswordMessageBuilder<'a> {
+        self.password = password;
+        self
+    }
+
+    pub fn build(self) -> PasswordMessage {
+        PasswordMessage::new(self.password)
+    }
+}
+
+impl Default for PasswordMessageBuilder<'_> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<'a> SASLInitialResponseBuilder<'a> {
+    pub fn new() -> SASLInitialResponseBuilder<'a> {
+        SASLInitialResponseBuilder {
+            mechanism: "SCRAM-SHA-256",
+            data: &[],
+        }
+    }
+
+    pub fn mechanism(
+        mut self, mechanism: &'a str
+    ) -> SASLInitialResponseBuilder<'a> {
+        self.mechanism = mechanism;
+        self
+    }
+
+    pub fn data(
+        mut self, data: &'a [u8]
+    ) -> SASLInitialResponseBuilder<'a> {
+        self.data = data;
+        self
+    }
+
+    pub fn build(self) -> SASLInitialResponse {
+        SASLInitialResponse::new(self.mechanism, self.data)
+    }
+}
+
+impl Default for SASLInitialResponseBuilder<'_> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<'a> SASLResponseBuilder<'a> {
+    pub fn new() -> SASLResponseBuilder<'a> {
+        SASLResponseBuilder {
+            data: &[],
+        }
+    }
+
+    pub fn data(
+        mut self, data: &'a [u8]
+    ) -> SASLResponseBuilder<'a> {
+        self.data = data;
+        self
+    }
+
+    pub fn build(self) -> SASLResponse {
+        SASLResponse::new(self.data)
+    }
+}
+
+impl Default for SASLResponseBuilder<'_> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<'a> ParseMessageBuilder<'a> {
+    pub fn new() -> ParseMessageBuilder<'a> {
+        ParseMessageBuilder {
+            name: "",
+            query: "SELECT 1;",
+            param_types: &[],
+        }
+    }
+
+    pub fn name(mut self, name: &'a str) -> ParseMessageBuilder<'a> {
+        self.name = name;
+        self
+    }
+
+    pub fn query(mut self, query: &'a str) -> ParseMessageBuilder<'a> {
+        self.query = query;
+        self
+    }
+
+    pub fn param_types(
+        mut self, param_types: &'a [u32]
+    ) -> ParseMessageBuilder<'a> {
+        self.param_types = param_types;
+        self
+    }
+
+    pub fn build(self) -> ParseMessage {
+        ParseMessage::new(self.name, self.query, self.param_types)
+    }
+}
+
+impl Default for ParseMessageBuilder<'_> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<'a> BindMessageBuilder<'a> {
+    pub fn new() -> BindMessageBuilder<'a> {
+        BindMessageBuilder {
+            portal: "",
+            statement: "",
+            param_formats: &[],
+            params: &[],
+            result_formats: &[],
+        }
+    }
+
+    pub fn portal(mut self, portal: &'a str) -> BindMessageBuilder<'a> {
+        self.portal = portal;
+        self
+    }
+
+    pub fn statement(mut self, statement: &'a str) -> BindMessageBuilder<'a> {
+        self.statement = statement;
+        self
+    }
+
+    pub fn param_formats(
+        mut self, param_formats: &'a [Format]
+    ) -> BindMessageBuilder<'a> {
+        self.param_formats = param_formats;
+        self
+    }
+
+    pub fn params(
+        mut self, params: &'a [Option<Vec<u8>>]
+    ) -> BindMessageBuilder<'a> {
+        self.params = params;
+        self
+    }
+
+    pub fn result_formats(
+        mut self, result_formats: &'a [Format]
+    ) -> BindMessageBuilder<'a> {
+        self.result_formats = result_formats;
+        self
+    }
+
+    pub fn build(self) -> BindMessage {
+        BindMessage::new(
+            self.portal,
+            self.statement,
+            self.param_formats,
+            self.params,
+            self.result_formats,
+        )
+    }
+}
+
+impl Default for BindMessageBuilder<'_> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<'a> DescribeMessageBuilder<'a> {
+    pub fn new() -> DescribeMessageBuilder<'a> {
+        DescribeMessageBuilder {
+            target: Target::Portal,
+            name: "",
+        }
+    }
+
+    pub fn target(mut self, target: Target) -> DescribeMessageBuilder<'a> {
+        self.target = target;
+        self
+    }
+
+    pub fn name(mut self, name: &'a str) -> DescribeMessageBuilder<'a> {
+        self.name = name;
+        self
+    }
+
+    pub fn build(self) -> DescribeMessage {
+        DescribeMessage::new(self.target, self.name)
+    }
+}
+
+impl Default for DescribeMessageBuilder<'_> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<'a> ExecuteMessageBuilder<'a> {
+    pub fn new() -> ExecuteMessageBuilder<'a> {
+        ExecuteMessageBuilder {
+            portal: "",
+            max_rows: 0,
+        }
+    }
+
+    pub fn portal(mut self, portal: &'a str) -> ExecuteMessageBuilder<'a> {
+        self.portal = portal;
+        self
+    }
+
+    pub fn max_rows(mut self, max_rows: i32) -> ExecuteMessageBuilder<'a> {
+        self.max_rows = max_rows;
+        self
+    }
+
+    pub fn build(self) -> ExecuteMessage {
+        ExecuteMessage::new(self.portal, self.max_rows)
+    }
+}
+
+impl Default for ExecuteMessageBuilder<'_> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SyncMessageBuilder {
+    pub fn new() -> SyncMessageBuilder {
+        SyncMessageBuilder
+    }
+
+    pub fn build(self) -> SyncMessage {
+        SyncMessage::new()
+    }
+}
+
+impl Default for SyncMessageBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<'a> CloseMessageBuilder<'a> {
+    pub fn new() -> CloseMessageBuilder<'a> {
+        CloseMessageBuilder {
+            target: Target::Statement,
+            name: "",
+        }
+    }
+
+    pub fn target(mut self, target: Target) -> CloseMessageBuilder<'a> {
+        self.target = target;
+        self
+    }
+
+    pub fn name(mut self, name: &'a str) -> CloseMessageBuilder<'a> {
+        self.name = name;
+        self
+    }
+
+    pub fn build(self) -> CloseMessage {
+        CloseMessage::new(self.target, self.name)
+    }
+}
+
+impl Default for CloseMessageBuilder<'_> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<'a> CopyDataMessageBuilder<'a> {
+    pub fn new() -> CopyDataMessageBuilder<'a> {
+        CopyDataMessageBuilder {
+            data: &[],
+        }
+    }
+
+    pub fn data(mut self, data: &'a [u8]) -> CopyDataMessageBuilder<'a> {
+        self.data = data;
+        self
+    }
+
+    pub fn build(self) -> CopyDataMessage {
+        CopyDataMessage::new(self.data)
+    }
+}
+
+impl Default for CopyDataMessageBuilder<'_> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl CopyDoneMessageBuilder {
+    pub fn new() -> CopyDoneMessageBuilder {
+        CopyDoneMessageBuilder
+    }
+
+    pub fn build(self) -> CopyDoneMessage {
+        CopyDoneMessage::new()
+    }
+}
+
+impl Default for CopyDoneMessageBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<'a> CopyFailMessageBuilder<'a> {
+    pub fn new() -> CopyFailMessageBuilder<'a> {
+        CopyFailMessageBuilder {
+            reason: "",
+        }
+    }
+
+    pub fn reason(mut self, reason: &'a str) -> CopyFailMessageBuilder<'a> {
+        self.reason = reason;
+        self
+    }
+
+    pub fn build(self) -> CopyFailMessage {
+        CopyFailMessage::new(self.reason)
+    }
+}
+
+impl Default for CopyFailMessageBuilder<'_> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl CancelRequestMessageBuilder {
+    pub fn new() -> CancelRequestMessageBuilder {
+        CancelRequestMessageBuilder {
+            process_id: 0,
+            secret_key: 0,
+        }
+    }
+
+    pub fn process_id(mut self, process_id: i32) -> CancelRequestMessageBuilder {
+        self.process_id = process_id;
+        self
+    }
+
+    pub fn secret_key(mut self, secret_key: i32) -> CancelRequestMessageBuilder {
+        self.secret_key = secret_key;
+        self
+    }
+
+    pub fn build(self) -> CancelRequestMessage {
+        CancelRequestMessage::new(self.process_id, self.secret_key)
+    }
+}
+
+impl Default for CancelRequestMessageBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SSLRequestMessageBuilder {
+    pub fn new() -> SSLRequestMessageBuilder {
+        SSLRequestMessageBuilder
+    }
+
+    pub fn build(self) -> SSLRequestMessage {
+        SSLRequestMessage::new()
+    }
+}
+
+impl Default for SSLRequestMessageBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -1,3 +1,6 @@
 pub mod front;
 pub mod front_builder;
 pub mod back;
+pub mod types;
+pub mod from_sql;
+pub mod to_sql;
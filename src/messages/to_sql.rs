@@ -0,0 +1,276 @@
+use super::types::{oid, Format};
+
+/// A Rust value that can be sent as a bind parameter.
+pub trait ToSql {
+    /// OID of the PostgreSQL type values of `Self` are encoded as.
+    fn oid() -> u32 where Self: Sized;
+
+    /// Same as [`ToSql::oid`], callable through `&dyn ToSql`.
+    fn type_oid(&self) -> u32;
+
+    /// Text representation of the value, `None` for NULL.
+    fn to_sql_text(&self) -> Option<Vec<u8>>;
+
+    /// Binary representation of the value, `None` for NULL.
+    fn to_sql_binary(&self) -> Option<Vec<u8>>;
+
+    fn to_sql(&self, format: Format) -> Option<Vec<u8>> {
+        match format {
+            Format::Text => self.to_sql_text(),
+            Format::Binary => self.to_sql_binary(),
+        }
+    }
+}
+
+impl<T: ToSql> ToSql for Option<T> {
+    fn oid() -> u32 {
+        T::oid()
+    }
+
+    fn type_oid(&self) -> u32 {
+        T::oid()
+    }
+
+    fn to_sql_text(&self) -> Option<Vec<u8>> {
+        self.as_ref().and_then(|value| value.to_sql_text())
+    }
+
+    fn to_sql_binary(&self) -> Option<Vec<u8>> {
+        self.as_ref().and_then(|value| value.to_sql_binary())
+    }
+}
+
+impl ToSql for bool {
+    fn oid() -> u32 {
+        oid::BOOL
+    }
+
+    fn type_oid(&self) -> u32 {
+        oid::BOOL
+    }
+
+    fn to_sql_text(&self) -> Option<Vec<u8>> {
+        Some(if *self { b"t".to_vec() } else { b"f".to_vec() })
+    }
+
+    fn to_sql_binary(&self) -> Option<Vec<u8>> {
+        Some(vec![*self as u8])
+    }
+}
+
+macro_rules! to_sql_integer {
+    ($t:ty, $oid:expr) => {
+        impl ToSql for $t {
+            fn oid() -> u32 {
+                $oid
+            }
+
+            fn type_oid(&self) -> u32 {
+                $oid
+            }
+
+            fn to_sql_text(&self) -> Option<Vec<u8>> {
+                Some(self.to_string().into_bytes())
+            }
+
+            fn to_sql_binary(&self) -> Option<Vec<u8>> {
+                Some(self.to_be_bytes().to_vec())
+            }
+        }
+    };
+}
+
+/// `i8` maps to the one-byte `"char"` type, so its text form is the byte
+/// itself like its binary one. Bytes that are not ASCII use the `\ooo`
+/// escape, as they would not pass the encoding check, and 0 is empty.
+impl ToSql for i8 {
+    fn oid() -> u32 {
+        oid::CHAR
+    }
+
+    fn type_oid(&self) -> u32 {
+        oid::CHAR
+    }
+
+    fn to_sql_text(&self) -> Option<Vec<u8>> {
+        Some(match *self as u8 {
+            0 => Vec::new(),
+            byte @ 1..=0x7f => vec![byte],
+            byte => format!("\\{:03o}", byte).into_bytes(),
+        })
+    }
+
+    fn to_sql_binary(&self) -> Option<Vec<u8>> {
+        Some(vec![*self as u8])
+    }
+}
+
+to_sql_integer!(i16, oid::INT2);
+to_sql_integer!(i32, oid::INT4);
+to_sql_integer!(i64, oid::INT8);
+to_sql_integer!(u32, oid::OID);
+
+macro_rules! to_sql_float {
+    ($t:ty, $oid:expr) => {
+        impl ToSql for $t {
+            fn oid() -> u32 {
+                $oid
+            }
+
+            fn type_oid(&self) -> u32 {
+                $oid
+            }
+
+            // Rust prints infinities as "inf", PostgreSQL spells them out.
+            fn to_sql_text(&self) -> Option<Vec<u8>> {
+                let text = if self.is_nan() {
+                    "NaN".to_string()
+                } else if self.is_infinite() {
+                    if *self > 0.0 { "Infinity" } else { "-Infinity" }.to_string()
+                } else {
+                    self.to_string()
+                };
+
+                Some(text.into_bytes())
+            }
+
+            fn to_sql_binary(&self) -> Option<Vec<u8>> {
+                Some(self.to_be_bytes().to_vec())
+            }
+        }
+    };
+}
+
+to_sql_float!(f32, oid::FLOAT4);
+to_sql_float!(f64, oid::FLOAT8);
+
+macro_rules! to_sql_text {
+    ($t:ty) => {
+        impl ToSql for $t {
+            fn oid() -> u32 {
+                oid::TEXT
+            }
+
+            fn type_oid(&self) -> u32 {
+                oid::TEXT
+            }
+
+            fn to_sql_text(&self) -> Option<Vec<u8>> {
+                Some(self.as_bytes().to_vec())
+            }
+
+            fn to_sql_binary(&self) -> Option<Vec<u8>> {
+                Some(self.as_bytes().to_vec())
+            }
+        }
+    };
+}
+
+to_sql_text!(&str);
+to_sql_text!(String);
+
+macro_rules! to_sql_bytea {
+    ($t:ty) => {
+        impl ToSql for $t {
+            fn oid() -> u32 {
+                oid::BYTEA
+            }
+
+            fn type_oid(&self) -> u32 {
+                oid::BYTEA
+            }
+
+            fn to_sql_text(&self) -> Option<Vec<u8>> {
+                let mut text = b"\\x".to_vec();
+                for byte in self.iter() {
+                    text.extend(format!("{:02x}", byte).as_bytes());
+                }
+
+                Some(text)
+            }
+
+            fn to_sql_binary(&self) -> Option<Vec<u8>> {
+                Some(self.to_vec())
+            }
+        }
+    };
+}
+
+to_sql_bytea!(&[u8]);
+to_sql_bytea!(Vec<u8>);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::messages::from_sql::FromSql;
+
+    fn round_trip<T: ToSql + FromSql>(value: &T, format: Format) -> T {
+        let raw = value.to_sql(format);
+        T::from_sql_nullable(value.type_oid(), format, raw.as_deref()).unwrap()
+    }
+
+    #[test]
+    fn test_bool_to_sql() {
+        assert_eq!(true.to_sql_text(), Some(b"t".to_vec()));
+        assert_eq!(false.to_sql_binary(), Some(vec![0]));
+        assert!(round_trip(&true, Format::Binary));
+        assert!(!round_trip(&false, Format::Text));
+    }
+
+    #[test]
+    fn test_integers_to_sql() {
+        assert_eq!((-5_i16).to_sql_text(), Some(b"-5".to_vec()));
+        assert_eq!(42_i32.to_sql_binary(), Some(vec![0, 0, 0, 42]));
+        assert_eq!(1_i64.to_sql_binary(), Some(vec![0, 0, 0, 0, 0, 0, 0, 1]));
+        assert_eq!(7_u32.type_oid(), oid::OID);
+        assert_eq!((b'A' as i8).to_sql_text(), Some(b"A".to_vec()));
+        assert_eq!((-1_i8).to_sql_text(), Some(b"\\377".to_vec()));
+        assert_eq!(0_i8.to_sql_text(), Some(Vec::new()));
+        assert_eq!((-1_i8).to_sql_binary(), Some(vec![0xff]));
+        assert_eq!(round_trip(&i16::MIN, Format::Binary), i16::MIN);
+        assert_eq!(round_trip(&i32::MAX, Format::Text), i32::MAX);
+        assert_eq!(round_trip(&i64::MIN, Format::Binary), i64::MIN);
+        assert_eq!(round_trip(&i64::MAX, Format::Text), i64::MAX);
+    }
+
+    #[test]
+    fn test_floats_to_sql() {
+        assert_eq!(f64::INFINITY.to_sql_text(), Some(b"Infinity".to_vec()));
+        assert_eq!(f32::NEG_INFINITY.to_sql_text(), Some(b"-Infinity".to_vec()));
+        assert_eq!(f64::NAN.to_sql_text(), Some(b"NaN".to_vec()));
+        assert_eq!(1.5_f32.to_sql_binary(), Some(1.5_f32.to_be_bytes().to_vec()));
+        assert_eq!(round_trip(&-0.125_f32, Format::Text), -0.125);
+        assert_eq!(round_trip(&f64::MAX, Format::Binary), f64::MAX);
+        assert_eq!(round_trip(&f64::NEG_INFINITY, Format::Text), f64::NEG_INFINITY);
+    }
+
+    #[test]
+    fn test_strings_to_sql() {
+        assert_eq!("abc".to_sql_text(), Some(b"abc".to_vec()));
+        assert_eq!("abc".type_oid(), oid::TEXT);
+        assert_eq!(round_trip(&"тест".to_string(), Format::Binary), "тест");
+        assert_eq!(round_trip(&String::new(), Format::Text), "");
+    }
+
+    #[test]
+    fn test_bytes_to_sql() {
+        let bytes: &[u8] = &[0xde, 0xad, 0x00];
+
+        assert_eq!(bytes.to_sql_text(), Some(b"\\xdead00".to_vec()));
+        assert_eq!(bytes.to_sql_binary(), Some(vec![0xde, 0xad, 0x00]));
+        assert_eq!(round_trip(&bytes.to_vec(), Format::Text), bytes);
+        assert_eq!(round_trip(&bytes.to_vec(), Format::Binary), bytes);
+    }
+
+    #[test]
+    fn test_option_to_sql() {
+        let none: Option<i32> = None;
+
+        assert_eq!(none.type_oid(), oid::INT4);
+        assert_eq!(none.to_sql_text(), None);
+        assert_eq!(none.to_sql_binary(), None);
+        assert_eq!(Some(3_i32).to_sql_binary(), Some(vec![0, 0, 0, 3]));
+        assert_eq!(round_trip(&none, Format::Binary), None);
+        assert_eq!(round_trip(&Some("x".to_string()), Format::Text), Some("x".to_string()));
+    }
+}
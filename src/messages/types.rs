@@ -0,0 +1,63 @@
+/// Wire format of a value, as used in RowDescription and Bind.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    Text,
+    Binary,
+}
+
+impl Format {
+    pub fn from_code(code: i16) -> Format {
+        match code {
+            1 => Format::Binary,
+            _ => Format::Text,
+        }
+    }
+
+    pub fn code(&self) -> i16 {
+        match self {
+            Format::Text => 0,
+            Format::Binary => 1,
+        }
+    }
+}
+
+/// Transaction status reported by every ReadyForQuery message.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TransactionStatus {
+    /// Not inside a transaction block (`I`).
+    Idle,
+    /// Inside a transaction block (`T`).
+    InTransaction,
+    /// Inside a failed transaction block, queries are rejected until
+    /// it is rolled back (`E`).
+    Failed,
+}
+
+impl TransactionStatus {
+    pub fn from_code(code: u8) -> Option<TransactionStatus> {
+        match code {
+            b'I' => Some(TransactionStatus::Idle),
+            b'T' => Some(TransactionStatus::InTransaction),
+            b'E' => Some(TransactionStatus::Failed),
+            _ => None,
+        }
+    }
+}
+
+/// OIDs of the built-in types from `pg_type`.
+pub mod oid {
+    pub const BOOL: u32 = 16;
+    pub const BYTEA: u32 = 17;
+    pub const CHAR: u32 = 18;
+    pub const NAME: u32 = 19;
+    pub const INT8: u32 = 20;
+    pub const INT2: u32 = 21;
+    pub const INT4: u32 = 23;
+    pub const TEXT: u32 = 25;
+    pub const OID: u32 = 26;
+    pub const FLOAT4: u32 = 700;
+    pub const FLOAT8: u32 = 701;
+    pub const UNKNOWN: u32 = 705;
+    pub const BPCHAR: u32 = 1042;
+    pub const VARCHAR: u32 = 1043;
+}
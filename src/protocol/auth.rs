@@ -0,0 +1,33 @@
+use md5::{Digest, Md5};
+
+/// Builds the response to `AuthenticationMD5Password`:
+/// `"md5" + md5(md5(password + user) + salt)` in lowercase hex.
+pub fn md5_password(user: &str, password: &str, salt: &[u8]) -> String {
+    let mut hasher = Md5::new();
+    hasher.update(password.as_bytes());
+    hasher.update(user.as_bytes());
+    let inner = to_hex(&hasher.finalize());
+
+    let mut hasher = Md5::new();
+    hasher.update(inner.as_bytes());
+    hasher.update(salt);
+
+    format!("md5{}", to_hex(&hasher.finalize()))
+}
+
+fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_md5_password() {
+        assert_eq!(
+            md5_password("postgres", "test", &[1, 2, 3, 4]),
+            "md5a5461c10b6d7d1eae2986aff9643ac57",
+        );
+    }
+}
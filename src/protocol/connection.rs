@@ -1,35 +1,453 @@
-use std::{io::Result, net::TcpStream};
-use std::io::Write;
+use std::io;
+use std::net::{SocketAddr, TcpStream, ToSocketAddrs};
+use std::io::{BufReader, Read, Write};
+use std::os::fd::{AsRawFd, RawFd};
+use std::os::unix::net::UnixStream;
+use std::path::Path;
+use std::time::{Duration, Instant};
+use libc::c_int;
+use crate::error::{Error, Result};
 use crate::messages::front::FrontendMessage;
-use crate::protocol::conn_params::ConnParams;
+use crate::messages::front_builder::SSLRequestMessageBuilder;
+use crate::protocol::conn_params::{ConnParams, Host, SslMode};
+use crate::protocol::stream::{wait_for_data, Stream};
 use crate::messages::back::BackMessage;
 
 pub struct Connection {
-    stream: TcpStream,
+    stream: BufReader<Stream>,
+    read_timeout: Option<Duration>,
+    broken: bool,
 }
 
 impl Connection {
     pub fn new(conn_params: &ConnParams) -> Result<Connection> {
-        let addr = format!(
-            "{}:{}",
-            conn_params.host().to_string(), 
-            conn_params.port().to_string(),
-        );
+        let port = u16::try_from(*conn_params.port())
+            .map_err(|_| Error::Config(format!("Invalid port {}", conn_params.port())))?;
+
+        let stream = match conn_params.host() {
+            // TLS is never used on local sockets, as in libpq.
+            Host::Unix(dir) => Stream::Unix(connect_unix(dir, port)?),
+            host => {
+                let stream = connect_tcp(&resolve(host, port, conn_params)?, conn_params)?;
+                start_tls(stream, conn_params)?
+            },
+        };
 
         Ok(Connection {
-            stream: match TcpStream::connect(addr) {
-                Ok(strm) => strm,
-                Err(err) => return Err(err),
-            } 
+            stream: BufReader::new(stream),
+            read_timeout: conn_params.read_timeout(),
+            broken: false,
         })
      }
 
     pub fn send_message<T: FrontendMessage>(
-        &mut self,  
+        &mut self,
         message: &mut T
     ) -> Result<BackMessage> {
-        self.stream.write_all(message.to_vecu8())?;
-        Ok(BackMessage::from_buf(&mut self.stream)?)
+        self.write_message(message)?;
+        self.read_message()
+    }
+
+    pub fn write_message<T: FrontendMessage>(
+        &mut self,
+        message: &mut T
+    ) -> Result<()> {
+        self.check_open()?;
+
+        let stream = self.stream.get_mut();
+        let result = stream.write_all(message.to_vecu8())
+            .and_then(|()| stream.flush());
+        self.broken = result.is_err();
+        Ok(result?)
+    }
+
+    /// Reads the next message, which must arrive in full within the read
+    /// timeout. A timeout before the first byte leaves the connection
+    /// usable, any other error breaks it.
+    pub fn read_message(&mut self) -> Result<BackMessage> {
+        self.check_open()?;
+
+        let mut reader = DeadlineReader {
+            stream: &mut self.stream,
+            deadline: self.read_timeout.map(|timeout| Instant::now() + timeout),
+            received: 0,
+        };
+        let result = BackMessage::from_buf(&mut reader);
+        let started = reader.received > 0;
+
+        match result {
+            Err(Error::Io(err)) if err.kind() == io::ErrorKind::TimedOut => {
+                self.broken = started;
+                Err(Error::Timeout(match started {
+                    false => "no data received from server".to_string(),
+                    true => "message only partly received from server".to_string(),
+                }))
+            },
+            Err(err) => {
+                self.broken = true;
+                Err(err)
+            },
+            Ok(message) => Ok(message),
+        }
+    }
+
+    /// Whether an earlier error left the connection unusable, in which
+    /// case every read and write fails with `Error::Closed`.
+    pub fn is_broken(&self) -> bool {
+        self.broken
+    }
+
+    /// Marks the connection unusable, e.g. when it could not be brought
+    /// back to ReadyForQuery.
+    pub(crate) fn set_broken(&mut self) {
+        self.broken = true;
+    }
+
+    fn check_open(&self) -> Result<()> {
+        match self.broken {
+            true => Err(Error::Closed),
+            false => Ok(()),
+        }
+    }
+
+    /// Waits until the server has sent something, returning `false` if
+    /// nothing arrived within `timeout`.
+    pub fn wait_readable(&mut self, timeout: Duration) -> Result<bool> {
+        if !self.stream.buffer().is_empty() {
+            return Ok(true);
+        }
+        self.stream.get_mut().wait_readable(Some(timeout))
+    }
+}
+
+/// Reads from the stream, failing with `TimedOut` once `deadline` has
+/// passed, so that a message stalling halfway does not block forever.
+struct DeadlineReader<'a> {
+    stream: &'a mut BufReader<Stream>,
+    deadline: Option<Instant>,
+    received: usize,
+}
+
+impl Read for DeadlineReader<'_> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        // Buffered data is not seen by poll, and needs no waiting anyway.
+        if let Some(deadline) = self.deadline.filter(|_| self.stream.buffer().is_empty()) {
+            let remaining = deadline.saturating_duration_since(Instant::now());
+            match self.stream.get_mut().wait_readable(Some(remaining)) {
+                Ok(true) => {},
+                Ok(false) => return Err(io::ErrorKind::TimedOut.into()),
+                Err(Error::Io(err)) => return Err(err),
+                Err(err) => return Err(io::Error::other(err.to_string())),
+            }
+            let remaining = deadline.saturating_duration_since(Instant::now());
+            self.stream.get_mut().set_read_timeout(Some(remaining))?;
+        }
+
+        let len = self.stream.read(buf).map_err(|err| match err.kind() {
+            io::ErrorKind::WouldBlock => io::ErrorKind::TimedOut.into(),
+            _ => err,
+        })?;
+        self.received += len;
+        Ok(len)
+    }
+}
+
+/// Addresses to try for a TCP host: `hostaddr` if set, else every
+/// address the host resolves to.
+fn resolve(host: &Host, port: u16, conn_params: &ConnParams) -> Result<Vec<SocketAddr>> {
+    if let Some(addr) = conn_params.hostaddr() {
+        return Ok(vec![SocketAddr::new(addr, port)]);
+    }
+
+    let addrs: Vec<SocketAddr> = match host {
+        Host::Ip(addr) => vec![SocketAddr::new(*addr, port)],
+        host => (host.to_string(), port).to_socket_addrs()
+            .map_err(|err| Error::Io(io::Error::new(
+                err.kind(),
+                format!("resolving {}: {}", host, err),
+            )))?
+            .collect(),
+    };
+
+    if addrs.is_empty() {
+        return Err(Error::Config(format!("Host {} has no addresses", host)));
+    }
+    Ok(addrs)
+}
+
+/// Connects to the first of `addrs` that accepts, returning the error of
+/// the last one if none does.
+fn connect_tcp(addrs: &[SocketAddr], conn_params: &ConnParams) -> Result<TcpStream> {
+    let mut result = Err(Error::Config("No address to connect to".to_string()));
+
+    for addr in addrs {
+        result = match conn_params.connect_timeout() {
+            Some(timeout) => TcpStream::connect_timeout(addr, timeout).map_err(|err| {
+                match err.kind() {
+                    io::ErrorKind::TimedOut => Error::Timeout(format!("connecting to {}", addr)),
+                    _ => Error::Io(err),
+                }
+            }),
+            None => TcpStream::connect(addr).map_err(Error::Io),
+        };
+
+        if result.is_ok() {
+            break;
+        }
     }
+
+    let stream = result?;
+    // Messages are written as soon as they are complete, so waiting to
+    // coalesce them only adds latency.
+    stream.set_nodelay(true)?;
+    set_keepalives(&stream, conn_params)?;
+    Ok(stream)
 }
 
+/// Applies the `keepalives*` and `tcp_user_timeout` parameters. Only
+/// `keepalives` itself is portable; the rest needs Linux.
+fn set_keepalives(stream: &TcpStream, conn_params: &ConnParams) -> Result<()> {
+    let fd = stream.as_raw_fd();
+    set_option(fd, libc::SOL_SOCKET, libc::SO_KEEPALIVE, conn_params.keepalives() as c_int)?;
+
+    #[cfg(any(target_os = "linux", target_os = "android"))]
+    {
+        let secs = |duration: Duration| duration.as_secs().min(c_int::MAX as u64) as c_int;
+        if conn_params.keepalives() {
+            if let Some(idle) = conn_params.keepalives_idle() {
+                set_option(fd, libc::IPPROTO_TCP, libc::TCP_KEEPIDLE, secs(idle))?;
+            }
+            if let Some(interval) = conn_params.keepalives_interval() {
+                set_option(fd, libc::IPPROTO_TCP, libc::TCP_KEEPINTVL, secs(interval))?;
+            }
+            if let Some(count) = conn_params.keepalives_count() {
+                set_option(fd, libc::IPPROTO_TCP, libc::TCP_KEEPCNT, count.min(c_int::MAX as u32) as c_int)?;
+            }
+        }
+        if let Some(timeout) = conn_params.tcp_user_timeout() {
+            let millis = timeout.as_millis().min(c_int::MAX as u128) as c_int;
+            set_option(fd, libc::IPPROTO_TCP, libc::TCP_USER_TIMEOUT, millis)?;
+        }
+    }
+
+    #[cfg(not(any(target_os = "linux", target_os = "android")))]
+    if conn_params.keepalives_idle().is_some()
+        || conn_params.keepalives_interval().is_some()
+        || conn_params.keepalives_count().is_some()
+        || conn_params.tcp_user_timeout().is_some()
+    {
+        return Err(Error::Config(
+            "keepalives_idle, keepalives_interval, keepalives_count and tcp_user_timeout \
+             are only supported on Linux".to_string()
+        ));
+    }
+
+    Ok(())
+}
+
+fn set_option(fd: RawFd, level: c_int, name: c_int, value: c_int) -> Result<()> {
+    let ret = unsafe {
+        libc::setsockopt(
+            fd,
+            level,
+            name,
+            &value as *const c_int as *const libc::c_void,
+            std::mem::size_of::<c_int>() as libc::socklen_t,
+        )
+    };
+    match ret {
+        0 => Ok(()),
+        _ => Err(Error::Io(io::Error::last_os_error())),
+    }
+}
+
+/// Connects to the socket file the server creates in `dir` for `port`.
+/// Connecting to a local socket does not block, so there is no timeout.
+fn connect_unix(dir: &Path, port: u16) -> Result<UnixStream> {
+    let path = dir.join(format!(".s.PGSQL.{}", port));
+
+    UnixStream::connect(&path).map_err(|err| Error::Io(io::Error::new(
+        err.kind(),
+        format!("connecting to {}: {}", path.display(), err),
+    )))
+}
+
+/// Negotiates TLS on a new connection according to its `sslmode`.
+fn start_tls(mut stream: TcpStream, conn_params: &ConnParams) -> Result<Stream> {
+    let mode = conn_params.ssl_mode();
+    if mode == SslMode::Disable || (mode == SslMode::Prefer && !cfg!(feature = "tls")) {
+        return Ok(Stream::Tcp(stream));
+    }
+    if !cfg!(feature = "tls") {
+        return Err(Error::Config(format!(
+            "sslmode={} requires the tls feature",
+            mode.as_str(),
+        )));
+    }
+
+    let mut request = SSLRequestMessageBuilder::new()
+        .build();
+    stream.write_all(request.to_vecu8())?;
+
+    wait_for_data(&stream.as_raw_fd(), conn_params.read_timeout())?;
+    let mut answer = [0; 1];
+    stream.read_exact(&mut answer)?;
+
+    match answer[0] {
+        #[cfg(feature = "tls")]
+        b'S' => crate::protocol::tls::connect(stream, conn_params),
+        b'N' if mode == SslMode::Prefer => Ok(Stream::Tcp(stream)),
+        b'N' => Err(Error::Tls("server does not support TLS".to_string())),
+        other => Err(Error::Protocol(format!(
+            "Invalid answer '{}' to SSLRequest",
+            other as char,
+        ))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::net::{Ipv4Addr, TcpListener};
+    use super::*;
+    use crate::protocol::conn_params::ConnParamsBuilder;
+
+    #[test]
+    fn test_connect_tries_every_address() -> Result<()> {
+        let listener = TcpListener::bind("127.0.0.1:0")?;
+        let open = listener.local_addr()?;
+        let closed = TcpListener::bind("127.0.0.1:0")?.local_addr()?;
+
+        let conn_params = ConnParamsBuilder::new()
+            .build();
+        let stream = connect_tcp(&[closed, open], &conn_params)?;
+        assert_eq!(stream.peer_addr()?, open);
+        assert!(stream.nodelay()?);
+        assert!(matches!(connect_tcp(&[closed], &conn_params), Err(Error::Io(_))));
+        Ok(())
+    }
+
+    #[cfg(target_os = "linux")]
+    fn get_option(stream: &TcpStream, level: c_int, name: c_int) -> c_int {
+        let mut value: c_int = -1;
+        let mut len = std::mem::size_of::<c_int>() as libc::socklen_t;
+        let ret = unsafe {
+            libc::getsockopt(
+                stream.as_raw_fd(),
+                level,
+                name,
+                &mut value as *mut c_int as *mut libc::c_void,
+                &mut len,
+            )
+        };
+        assert_eq!(ret, 0);
+        value
+    }
+
+    #[test]
+    #[cfg(target_os = "linux")]
+    fn test_keepalives() -> Result<()> {
+        let listener = TcpListener::bind("127.0.0.1:0")?;
+        let addr = listener.local_addr()?;
+
+        let stream = connect_tcp(&[addr], &ConnParamsBuilder::new().build())?;
+        assert_eq!(get_option(&stream, libc::SOL_SOCKET, libc::SO_KEEPALIVE), 1);
+
+        let conn_params = ConnParamsBuilder::new()
+            .keepalives(false)
+            .build();
+        let stream = connect_tcp(&[addr], &conn_params)?;
+        assert_eq!(get_option(&stream, libc::SOL_SOCKET, libc::SO_KEEPALIVE), 0);
+
+        let conn_params = ConnParamsBuilder::new()
+            .keepalives_idle(Some(Duration::from_secs(30)))
+            .keepalives_interval(Some(Duration::from_secs(5)))
+            .keepalives_count(Some(3))
+            .tcp_user_timeout(Some(Duration::from_millis(2500)))
+            .build();
+        let stream = connect_tcp(&[addr], &conn_params)?;
+        assert_eq!(get_option(&stream, libc::IPPROTO_TCP, libc::TCP_KEEPIDLE), 30);
+        assert_eq!(get_option(&stream, libc::IPPROTO_TCP, libc::TCP_KEEPINTVL), 5);
+        assert_eq!(get_option(&stream, libc::IPPROTO_TCP, libc::TCP_KEEPCNT), 3);
+        assert_eq!(get_option(&stream, libc::IPPROTO_TCP, libc::TCP_USER_TIMEOUT), 2500);
+        Ok(())
+    }
+
+    #[test]
+    fn test_read_timeout_covers_whole_message() -> Result<()> {
+        let listener = TcpListener::bind("127.0.0.1:0")?;
+        let port = listener.local_addr()?.port() as u32;
+        let server = std::thread::spawn(move || -> io::Result<()> {
+            let (mut stream, _) = listener.accept()?;
+            stream.write_all(b"Z\0\0\0\x05I")?;
+            // The header of a second message, without its body.
+            stream.write_all(b"Z\0\0\0\x05")?;
+            std::thread::sleep(Duration::from_millis(500));
+            Ok(())
+        });
+
+        let conn_params = ConnParamsBuilder::new()
+            .port(port)
+            .ssl_mode(SslMode::Disable)
+            .read_timeout(Some(Duration::from_millis(100)))
+            .build();
+        let mut conn = Connection::new(&conn_params)?;
+        conn.read_message()?;
+        assert!(!conn.is_broken());
+
+        let started = Instant::now();
+        assert!(matches!(conn.read_message(), Err(Error::Timeout(_))));
+        assert!(started.elapsed() < Duration::from_millis(400));
+        assert!(conn.is_broken());
+        assert!(matches!(conn.read_message(), Err(Error::Closed)));
+        server.join().unwrap()?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_buffered_messages_are_readable() -> Result<()> {
+        let listener = TcpListener::bind("127.0.0.1:0")?;
+        let port = listener.local_addr()?.port() as u32;
+        let server = std::thread::spawn(move || -> io::Result<()> {
+            let (mut stream, _) = listener.accept()?;
+            stream.write_all(b"Z\0\0\0\x05IZ\0\0\0\x05T")?;
+            std::thread::sleep(Duration::from_millis(300));
+            Ok(())
+        });
+
+        let conn_params = ConnParamsBuilder::new()
+            .port(port)
+            .ssl_mode(SslMode::Disable)
+            .read_timeout(Some(Duration::from_millis(100)))
+            .build();
+        let mut conn = Connection::new(&conn_params)?;
+        std::thread::sleep(Duration::from_millis(50));
+        conn.read_message()?;
+
+        // The second message already sits in the read buffer.
+        assert!(conn.wait_readable(Duration::ZERO)?);
+        assert_eq!(conn.read_message()?.into_data(), b"T");
+        assert!(!conn.wait_readable(Duration::ZERO)?);
+        server.join().unwrap()?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_resolve() -> Result<()> {
+        let conn_params = ConnParamsBuilder::new()
+            .build();
+        let addrs = resolve(&Host::Name("localhost".to_string()), 5432, &conn_params)?;
+        assert!(addrs.iter().all(|addr| addr.ip().is_loopback() && addr.port() == 5432));
+
+        let conn_params = ConnParamsBuilder::new()
+            .hostaddr(Some(Ipv4Addr::new(10, 0, 0, 1).into()))
+            .build();
+        let addrs = resolve(&Host::Name("db.invalid".to_string()), 5432, &conn_params)?;
+        assert_eq!(addrs, [SocketAddr::from(([10, 0, 0, 1], 5432))]);
+
+        assert!(matches!(
+            resolve(&Host::Name("db.invalid".to_string()), 5432, &ConnParamsBuilder::new().build()),
+            Err(Error::Io(_)),
+        ));
+        Ok(())
+    }
+}
@@ -1,2 +1,7 @@
 pub mod connection;
 pub mod conn_params;
+pub mod auth;
+pub mod scram;
+pub(crate) mod stream;
+#[cfg(feature = "tls")]
+mod tls;
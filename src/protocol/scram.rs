@@ -0,0 +1,252 @@
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD;
+use hmac::{Hmac, Mac};
+use sha2::{Digest, Sha256};
+use crate::error::Error;
+
+pub const SCRAM_SHA_256: &str = "SCRAM-SHA-256";
+
+// Channel binding is not supported, so the GS2 header is always "n,,"
+// and its base64 form "biws" is sent back in the client-final message.
+const GS2_HEADER: &str = "n,,";
+const NONCE_LEN: usize = 18;
+// PostgreSQL uses 4096 by default. The bound keeps a hostile server from
+// making the client spend minutes in PBKDF2.
+const MAX_ITERATIONS: u32 = 1_000_000;
+
+enum State {
+    Initial,
+    Continued {
+        salted_password: [u8; 32],
+        auth_message: String,
+    },
+    Finished,
+}
+
+/// Client side of a SCRAM-SHA-256 exchange (RFC 5802, RFC 7677).
+///
+/// The user name is left empty in the client-first message, as
+/// PostgreSQL takes it from the StartupMessage instead. The password is
+/// normalized with SASLprep, and used as is when that fails, as the
+/// server does.
+pub struct ScramSha256 {
+    password: String,
+    client_nonce: String,
+    state: State,
+}
+
+impl ScramSha256 {
+    pub fn new(password: &str) -> ScramSha256 {
+        let nonce: [u8; NONCE_LEN] = rand::random();
+        ScramSha256::with_nonce(password, &STANDARD.encode(nonce))
+    }
+
+    pub(crate) fn with_nonce(password: &str, nonce: &str) -> ScramSha256 {
+        ScramSha256 {
+            password: stringprep::saslprep(password)
+                .map(|password| password.into_owned())
+                .unwrap_or_else(|_| password.to_string()),
+            client_nonce: nonce.to_string(),
+            state: State::Initial,
+        }
+    }
+
+    pub fn client_first_message(&self) -> Vec<u8> {
+        format!("{}{}", GS2_HEADER, self.client_first_bare()).into_bytes()
+    }
+
+    /// Consumes the server-first message and returns the client-final one.
+    pub fn update(&mut self, server_first: &[u8]) -> Result<Vec<u8>, Error> {
+        if !matches!(self.state, State::Initial) {
+            return Err(auth_error("SCRAM: unexpected server-first message"));
+        }
+
+        let server_first = std::str::from_utf8(server_first)
+            .map_err(|_| auth_error("SCRAM: server-first message is not valid UTF-8"))?;
+
+        let mut nonce = None;
+        let mut salt = None;
+        let mut iterations = None;
+
+        for attr in server_first.split(',') {
+            match attr.split_once('=') {
+                Some(("r", value)) => nonce = Some(value),
+                Some(("s", value)) => salt = Some(value),
+                Some(("i", value)) => iterations = Some(value),
+                _ => {},
+            }
+        }
+
+        let nonce = nonce.ok_or_else(|| auth_error("SCRAM: server-first message has no nonce"))?;
+        let salt = salt.ok_or_else(|| auth_error("SCRAM: server-first message has no salt"))?;
+        let iterations: u32 = iterations
+            .ok_or_else(|| auth_error("SCRAM: server-first message has no iteration count"))?
+            .parse()
+            .map_err(|_| auth_error("SCRAM: invalid iteration count"))?;
+        if !(1..=MAX_ITERATIONS).contains(&iterations) {
+            return Err(auth_error(&format!(
+                "SCRAM: iteration count {} is outside 1..={}",
+                iterations,
+                MAX_ITERATIONS,
+            )));
+        }
+
+        if !nonce.starts_with(&self.client_nonce) || nonce.len() == self.client_nonce.len() {
+            return Err(auth_error("SCRAM: server nonce does not extend client nonce"));
+        }
+
+        let salt = STANDARD.decode(salt)
+            .map_err(|_| auth_error("SCRAM: salt is not valid base64"))?;
+
+        let mut salted_password = [0u8; 32];
+        pbkdf2::pbkdf2_hmac::<Sha256>(
+            self.password.as_bytes(),
+            &salt,
+            iterations,
+            &mut salted_password,
+        );
+
+        let client_final_without_proof = format!(
+            "c={},r={}",
+            STANDARD.encode(GS2_HEADER),
+            nonce,
+        );
+        let auth_message = format!(
+            "{},{},{}",
+            self.client_first_bare(),
+            server_first,
+            client_final_without_proof,
+        );
+
+        let client_key = hmac(&salted_password, b"Client Key");
+        let stored_key = Sha256::digest(client_key);
+        let client_signature = hmac(&stored_key, auth_message.as_bytes());
+
+        let proof: Vec<u8> = client_key.iter()
+            .zip(client_signature.iter())
+            .map(|(key, sig)| key ^ sig)
+            .collect();
+
+        self.state = State::Continued { salted_password, auth_message };
+
+        Ok(format!("{},p={}", client_final_without_proof, STANDARD.encode(proof)).into_bytes())
+    }
+
+    /// Verifies the server signature carried by the server-final message.
+    pub fn finish(&mut self, server_final: &[u8]) -> Result<(), Error> {
+        let (salted_password, auth_message) = match &self.state {
+            State::Continued { salted_password, auth_message } => (salted_password, auth_message),
+            _ => return Err(auth_error("SCRAM: unexpected server-final message")),
+        };
+
+        let server_final = std::str::from_utf8(server_final)
+            .map_err(|_| auth_error("SCRAM: server-final message is not valid UTF-8"))?;
+
+        if let Some(err) = server_final.strip_prefix("e=") {
+            return Err(auth_error(&format!("SCRAM: server error: {}", err)));
+        }
+
+        let verifier = server_final.split(',')
+            .find_map(|attr| attr.strip_prefix("v="))
+            .ok_or_else(|| auth_error("SCRAM: server-final message has no verifier"))?;
+        let verifier = STANDARD.decode(verifier)
+            .map_err(|_| auth_error("SCRAM: verifier is not valid base64"))?;
+
+        let server_key = hmac(salted_password, b"Server Key");
+        let mut mac = Hmac::<Sha256>::new_from_slice(&server_key)
+            .expect("HMAC accepts keys of any length");
+        mac.update(auth_message.as_bytes());
+        mac.verify_slice(&verifier)
+            .map_err(|_| auth_error("SCRAM: invalid server signature"))?;
+
+        self.state = State::Finished;
+        Ok(())
+    }
+
+    /// Whether the server signature has been verified.
+    pub fn is_finished(&self) -> bool {
+        matches!(self.state, State::Finished)
+    }
+
+    fn client_first_bare(&self) -> String {
+        format!("n=,r={}", self.client_nonce)
+    }
+}
+
+fn auth_error(msg: &str) -> Error {
+    Error::Authentication(msg.to_string())
+}
+
+fn hmac(key: &[u8], data: &[u8]) -> [u8; 32] {
+    let mut mac = Hmac::<Sha256>::new_from_slice(key)
+        .expect("HMAC accepts keys of any length");
+    mac.update(data);
+    mac.finalize().into_bytes().into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const NONCE: &str = "rOprNGfwEbeRWgbNEkqO";
+    const SERVER_FIRST: &[u8] = b"r=rOprNGfwEbeRWgbNEkqO%hvYDpWUa2RaTCAfuxFIlj)hNlF$k0,\
+        s=W22ZaJ0SNY7soEsUEjb6gQ==,i=4096";
+    const CLIENT_FINAL: &[u8] = b"c=biws,r=rOprNGfwEbeRWgbNEkqO%hvYDpWUa2RaTCAfuxFIlj)hNlF$k0,\
+        p=qvT2SWdEH5Q06albL+hjSYuUhCG7VndFyzIb7CK4n9k=";
+    const SERVER_FINAL: &[u8] = b"v=3HO6Qt1M4MKJrmlKaoOqLAI0/0TV0HZe7J9H3MBtSOg=";
+
+    #[test]
+    fn test_exchange() {
+        let mut scram = ScramSha256::with_nonce("pencil", NONCE);
+
+        assert_eq!(scram.client_first_message(), b"n,,n=,r=rOprNGfwEbeRWgbNEkqO");
+        assert_eq!(scram.update(SERVER_FIRST).unwrap(), CLIENT_FINAL);
+        assert!(!scram.is_finished());
+        assert!(scram.finish(SERVER_FINAL).is_ok());
+        assert!(scram.is_finished());
+    }
+
+    #[test]
+    fn test_iteration_count_bounds() {
+        for iterations in ["0", "1000001", "4294967295"] {
+            let mut scram = ScramSha256::with_nonce("pencil", NONCE);
+            let server_first = format!(
+                "r=rOprNGfwEbeRWgbNEkqO%hvYDpWUa2RaTCAfuxFIlj)hNlF$k0,s=W22ZaJ0SNY7soEsUEjb6gQ==,i={}",
+                iterations,
+            );
+            assert!(scram.update(server_first.as_bytes()).is_err(), "{}", iterations);
+        }
+    }
+
+    #[test]
+    fn test_saslprep() {
+        // U+00A0 NO-BREAK SPACE maps to a space, U+00AD SOFT HYPHEN to
+        // nothing.
+        assert_eq!(ScramSha256::new("pen\u{a0}cil\u{ad}").password, "pen cil");
+        // Prohibited characters leave the password as is.
+        assert_eq!(ScramSha256::new("pen\u{7}cil").password, "pen\u{7}cil");
+    }
+
+    #[test]
+    fn test_invalid_server_signature() {
+        let mut scram = ScramSha256::with_nonce("pencil", NONCE);
+
+        scram.update(SERVER_FIRST).unwrap();
+        assert!(scram.finish(b"v=AAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAA=").is_err());
+    }
+
+    #[test]
+    fn test_foreign_nonce() {
+        let mut scram = ScramSha256::with_nonce("pencil", "other");
+
+        assert!(scram.update(SERVER_FIRST).is_err());
+    }
+
+    #[test]
+    fn test_server_error() {
+        let mut scram = ScramSha256::with_nonce("pencil", NONCE);
+
+        scram.update(SERVER_FIRST).unwrap();
+        assert!(scram.finish(b"e=invalid-proof").is_err());
+    }
+}
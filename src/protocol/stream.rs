@@ -0,0 +1,160 @@
+use std::io::{self, Read, Write};
+use std::net::TcpStream;
+use std::os::fd::AsRawFd;
+use std::os::unix::net::UnixStream;
+use std::os::unix::io::RawFd;
+use std::time::{Duration, Instant};
+use libc::{poll, pollfd, POLLIN};
+use crate::error::Error;
+
+/// The socket of a connection: TCP, optionally wrapped in TLS, or a
+/// Unix domain socket.
+pub(crate) enum Stream {
+    Tcp(TcpStream),
+    Unix(UnixStream),
+    #[cfg(feature = "tls")]
+    Tls(Box<rustls::StreamOwned<rustls::ClientConnection, TcpStream>>),
+}
+
+impl Stream {
+    /// Waits until a read would not block, returning `false` if nothing
+    /// arrived within `timeout`; `None` waits forever.
+    pub(crate) fn wait_readable(&mut self, timeout: Option<Duration>) -> Result<bool, Error> {
+        #[cfg(feature = "tls")]
+        if let Stream::Tls(tls) = self {
+            // Records already read from the socket are not seen by poll.
+            let state = tls.conn.process_new_packets()
+                .map_err(|err| Error::Tls(err.to_string()))?;
+            if state.plaintext_bytes_to_read() > 0 {
+                return Ok(true);
+            }
+        }
+
+        match wait_for_data(&self.as_raw_fd(), timeout) {
+            Ok(()) => Ok(true),
+            Err(Error::Timeout(_)) => Ok(false),
+            Err(err) => Err(err),
+        }
+    }
+
+    /// Limits how long a read may block once poll has reported data.
+    /// Only TLS needs it, as the data may be just part of a record.
+    #[cfg_attr(not(feature = "tls"), allow(unused_variables))]
+    pub(crate) fn set_read_timeout(&mut self, timeout: Option<Duration>) -> io::Result<()> {
+        match self {
+            #[cfg(feature = "tls")]
+            Stream::Tls(tls) => {
+                // A zero timeout is rejected, and would mean none.
+                tls.sock.set_read_timeout(timeout.map(|timeout| timeout.max(Duration::from_millis(1))))
+            },
+            _ => Ok(()),
+        }
+    }
+
+    fn as_raw_fd(&self) -> RawFd {
+        match self {
+            Stream::Tcp(stream) => stream.as_raw_fd(),
+            Stream::Unix(stream) => stream.as_raw_fd(),
+            #[cfg(feature = "tls")]
+            Stream::Tls(tls) => tls.sock.as_raw_fd(),
+        }
+    }
+}
+
+impl Read for Stream {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        match self {
+            Stream::Tcp(stream) => stream.read(buf),
+            Stream::Unix(stream) => stream.read(buf),
+            #[cfg(feature = "tls")]
+            Stream::Tls(tls) => tls.read(buf),
+        }
+    }
+}
+
+impl Write for Stream {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        match self {
+            Stream::Tcp(stream) => stream.write(buf),
+            Stream::Unix(stream) => stream.write(buf),
+            #[cfg(feature = "tls")]
+            Stream::Tls(tls) => tls.write(buf),
+        }
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        match self {
+            Stream::Tcp(stream) => stream.flush(),
+            Stream::Unix(stream) => stream.flush(),
+            #[cfg(feature = "tls")]
+            Stream::Tls(tls) => tls.flush(),
+        }
+    }
+}
+
+/// Converts a timeout to poll milliseconds, rounding up so that poll does
+/// not return before the timeout; `None` waits forever.
+fn poll_timeout_ms(timeout: Option<Duration>) -> i32 {
+    match timeout {
+        Some(timeout) => timeout.as_nanos().div_ceil(1_000_000).min(i32::MAX as u128) as i32,
+        None => -1,
+    }
+}
+
+/// Waits until `fd` is readable. Signals interrupting poll restart the
+/// wait for the time that is left.
+pub(crate) fn wait_for_data(fd: &RawFd, timeout: Option<Duration>) -> Result<(), Error> {
+    let deadline = timeout.map(|timeout| Instant::now() + timeout);
+
+    loop {
+        let mut fds = [pollfd {
+            fd: *fd,
+            events: POLLIN,
+            revents: 0,
+        }];
+        let remaining = deadline.map(|deadline| deadline.saturating_duration_since(Instant::now()));
+
+        let ret = unsafe { poll(fds.as_mut_ptr(), 1, poll_timeout_ms(remaining)) };
+
+        if ret == -1 {
+            let err = io::Error::last_os_error();
+            if err.kind() == io::ErrorKind::Interrupted {
+                continue;
+            }
+            return Err(Error::Io(err));
+        } else if ret == 0 {
+            return Err(Error::Timeout("no data received from server".to_string()));
+        }
+
+        return Ok(());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::mpsc;
+    use std::thread;
+    use super::*;
+
+    extern "C" fn ignore_signal(_: libc::c_int) {}
+
+    #[test]
+    fn test_wait_for_data_survives_signals() -> Result<(), Error> {
+        let (reader, mut writer) = UnixStream::pair()?;
+        unsafe { libc::signal(libc::SIGUSR1, ignore_signal as *const () as libc::sighandler_t) };
+
+        let (tx, rx) = mpsc::channel();
+        let waiter = thread::spawn(move || {
+            tx.send(unsafe { libc::pthread_self() }).unwrap();
+            wait_for_data(&reader.as_raw_fd(), Some(Duration::from_secs(2)))
+        });
+
+        let thread = rx.recv().unwrap();
+        thread::sleep(Duration::from_millis(50));
+        unsafe { libc::pthread_kill(thread, libc::SIGUSR1) };
+        thread::sleep(Duration::from_millis(50));
+        writer.write_all(b"x")?;
+
+        waiter.join().unwrap()
+    }
+}
@@ -0,0 +1,208 @@
+use std::fs::File;
+use std::io::BufReader;
+use std::net::TcpStream;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use rustls::client::danger::{HandshakeSignatureValid, ServerCertVerified, ServerCertVerifier};
+use rustls::client::WebPkiServerVerifier;
+use rustls::crypto::{ring, verify_tls12_signature, verify_tls13_signature, CryptoProvider};
+use rustls::pki_types::{CertificateDer, PrivateKeyDer, ServerName, UnixTime};
+use rustls::{
+    CertificateError,
+    ClientConfig,
+    ClientConnection,
+    DigitallySignedStruct,
+    RootCertStore,
+    SignatureScheme,
+    StreamOwned,
+};
+use crate::error::Error;
+use crate::protocol::conn_params::{ConnParams, Host, SslMode};
+use crate::protocol::stream::Stream;
+
+/// Runs the TLS handshake on a connection the server agreed to encrypt.
+///
+/// Handshake and certificate errors are reported here rather than on the
+/// first query.
+pub(crate) fn connect(mut stream: TcpStream, conn_params: &ConnParams) -> Result<Stream, Error> {
+    let provider = Arc::new(ring::default_provider());
+    let builder = ClientConfig::builder_with_provider(provider.clone())
+        .with_safe_default_protocol_versions()
+        .map_err(tls_error)?;
+
+    let verifier: Arc<dyn ServerCertVerifier> = match (conn_params.ssl_mode(), root_cert(conn_params)) {
+        (SslMode::VerifyFull, Some(path)) => webpki_verifier(&path, provider)?,
+        (SslMode::VerifyCa, Some(path)) => Arc::new(NoNameVerifier(webpki_verifier(&path, provider)?)),
+        (SslMode::VerifyFull | SslMode::VerifyCa, None) => {
+            return Err(Error::Config(format!(
+                "sslmode={} requires sslrootcert or ~/.postgresql/root.crt",
+                conn_params.ssl_mode().as_str(),
+            )));
+        },
+        // Like libpq, require behaves as verify-ca once a root certificate
+        // is available.
+        (_, Some(path)) => Arc::new(NoNameVerifier(webpki_verifier(&path, provider)?)),
+        (_, None) => Arc::new(NoVerifier(provider)),
+    };
+    let builder = builder.dangerous().with_custom_certificate_verifier(verifier);
+
+    let mut config = match (conn_params.ssl_cert(), conn_params.ssl_key()) {
+        (Some(cert), Some(key)) => builder
+            .with_client_auth_cert(load_certs(cert)?, load_key(key)?)
+            .map_err(tls_error)?,
+        (None, None) => builder.with_no_client_auth(),
+        _ => return Err(Error::Config("sslcert and sslkey must be set together".to_string())),
+    };
+    config.enable_sni = conn_params.ssl_sni();
+
+    // The certificate is checked against the host as given, even when
+    // hostaddr says where to connect.
+    let server_name = match conn_params.host() {
+        Host::Name(name) => ServerName::try_from(name.clone()).map_err(|err| {
+            Error::Config(format!("Invalid host name for TLS '{}': {}", name, err))
+        })?,
+        Host::Ip(addr) => ServerName::IpAddress((*addr).into()),
+        Host::Unix(_) => unreachable!("TLS is not used on Unix domain sockets"),
+    };
+    let mut conn = ClientConnection::new(Arc::new(config), server_name).map_err(tls_error)?;
+    while conn.is_handshaking() {
+        conn.complete_io(&mut stream).map_err(|err| Error::Tls(err.to_string()))?;
+    }
+
+    Ok(Stream::Tls(Box::new(StreamOwned::new(conn, stream))))
+}
+
+/// The configured root certificate, or libpq's default location if that
+/// file exists.
+fn root_cert(conn_params: &ConnParams) -> Option<PathBuf> {
+    if let Some(path) = conn_params.ssl_root_cert() {
+        return Some(path.to_path_buf());
+    }
+
+    let path = PathBuf::from(std::env::var_os("HOME")?).join(".postgresql/root.crt");
+    path.exists().then_some(path)
+}
+
+fn webpki_verifier(
+    path: &Path, provider: Arc<CryptoProvider>
+) -> Result<Arc<WebPkiServerVerifier>, Error> {
+    let mut roots = RootCertStore::empty();
+    for cert in load_certs(path)? {
+        roots.add(cert).map_err(tls_error)?;
+    }
+
+    WebPkiServerVerifier::builder_with_provider(Arc::new(roots), provider)
+        .build()
+        .map_err(|err| Error::Config(format!("Invalid sslrootcert: {}", err)))
+}
+
+fn load_certs(path: &Path) -> Result<Vec<CertificateDer<'static>>, Error> {
+    let mut reader = BufReader::new(open(path)?);
+    let certs = rustls_pemfile::certs(&mut reader)
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|err| Error::Config(format!("Cannot read {}: {}", path.display(), err)))?;
+
+    if certs.is_empty() {
+        return Err(Error::Config(format!("No certificates in {}", path.display())));
+    }
+    Ok(certs)
+}
+
+fn load_key(path: &Path) -> Result<PrivateKeyDer<'static>, Error> {
+    let mut reader = BufReader::new(open(path)?);
+    rustls_pemfile::private_key(&mut reader)
+        .map_err(|err| Error::Config(format!("Cannot read {}: {}", path.display(), err)))?
+        .ok_or_else(|| Error::Config(format!("No private key in {}", path.display())))
+}
+
+fn open(path: &Path) -> Result<File, Error> {
+    File::open(path).map_err(|err| Error::Config(format!("Cannot open {}: {}", path.display(), err)))
+}
+
+fn tls_error(err: rustls::Error) -> Error {
+    Error::Tls(err.to_string())
+}
+
+/// Accepts any server certificate, for `prefer` and `require` without a
+/// root certificate. Handshake signatures are still checked.
+#[derive(Debug)]
+struct NoVerifier(Arc<CryptoProvider>);
+
+impl ServerCertVerifier for NoVerifier {
+    fn verify_server_cert(
+        &self,
+        _end_entity: &CertificateDer<'_>,
+        _intermediates: &[CertificateDer<'_>],
+        _server_name: &ServerName<'_>,
+        _ocsp_response: &[u8],
+        _now: UnixTime,
+    ) -> Result<ServerCertVerified, rustls::Error> {
+        Ok(ServerCertVerified::assertion())
+    }
+
+    fn verify_tls12_signature(
+        &self,
+        message: &[u8],
+        cert: &CertificateDer<'_>,
+        dss: &DigitallySignedStruct,
+    ) -> Result<HandshakeSignatureValid, rustls::Error> {
+        verify_tls12_signature(message, cert, dss, &self.0.signature_verification_algorithms)
+    }
+
+    fn verify_tls13_signature(
+        &self,
+        message: &[u8],
+        cert: &CertificateDer<'_>,
+        dss: &DigitallySignedStruct,
+    ) -> Result<HandshakeSignatureValid, rustls::Error> {
+        verify_tls13_signature(message, cert, dss, &self.0.signature_verification_algorithms)
+    }
+
+    fn supported_verify_schemes(&self) -> Vec<SignatureScheme> {
+        self.0.signature_verification_algorithms.supported_schemes()
+    }
+}
+
+/// Checks the certificate chain but not the host name, for `verify-ca`.
+#[derive(Debug)]
+struct NoNameVerifier(Arc<WebPkiServerVerifier>);
+
+impl ServerCertVerifier for NoNameVerifier {
+    fn verify_server_cert(
+        &self,
+        end_entity: &CertificateDer<'_>,
+        intermediates: &[CertificateDer<'_>],
+        server_name: &ServerName<'_>,
+        ocsp_response: &[u8],
+        now: UnixTime,
+    ) -> Result<ServerCertVerified, rustls::Error> {
+        match self.0.verify_server_cert(end_entity, intermediates, server_name, ocsp_response, now) {
+            Err(rustls::Error::InvalidCertificate(
+                CertificateError::NotValidForName | CertificateError::NotValidForNameContext { .. },
+            )) => Ok(ServerCertVerified::assertion()),
+            result => result,
+        }
+    }
+
+    fn verify_tls12_signature(
+        &self,
+        message: &[u8],
+        cert: &CertificateDer<'_>,
+        dss: &DigitallySignedStruct,
+    ) -> Result<HandshakeSignatureValid, rustls::Error> {
+        self.0.verify_tls12_signature(message, cert, dss)
+    }
+
+    fn verify_tls13_signature(
+        &self,
+        message: &[u8],
+        cert: &CertificateDer<'_>,
+        dss: &DigitallySignedStruct,
+    ) -> Result<HandshakeSignatureValid, rustls::Error> {
+        self.0.verify_tls13_signature(message, cert, dss)
+    }
+
+    fn supported_verify_schemes(&self) -> Vec<SignatureScheme> {
+        self.0.supported_verify_schemes()
+    }
+}
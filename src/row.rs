@@ -0,0 +1,148 @@
+use std::sync::Arc;
+use crate::messages::back::FieldDescription;
+use crate::error::Error;
+use crate::messages::from_sql::FromSql;
+
+/// A single row returned by a query.
+pub struct Row {
+    columns: Arc<Vec<FieldDescription>>,
+    values: Vec<Option<Vec<u8>>>,
+}
+
+/// Something that can select a column of a [`Row`]: a position or a name.
+pub trait RowIndex {
+    fn index(&self, columns: &[FieldDescription]) -> Option<usize>;
+}
+
+impl RowIndex for usize {
+    fn index(&self, columns: &[FieldDescription]) -> Option<usize> {
+        if *self < columns.len() {
+            Some(*self)
+        } else {
+            None
+        }
+    }
+}
+
+impl RowIndex for &str {
+    fn index(&self, columns: &[FieldDescription]) -> Option<usize> {
+        columns.iter().position(|column| column.name() == *self)
+    }
+}
+
+impl Row {
+    pub(crate) fn new(
+        columns: Arc<Vec<FieldDescription>>, values: Vec<Option<Vec<u8>>>
+    ) -> Row {
+        Row { columns, values }
+    }
+
+    #[inline]
+    pub fn columns(&self) -> &[FieldDescription] {
+        &self.columns
+    }
+
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    /// Decodes a column into `T`; use `Option<T>` for nullable columns.
+    pub fn get<T: FromSql>(&self, idx: impl RowIndex) -> Result<T, Error> {
+        let idx = self.position(idx)?;
+        let column = &self.columns[idx];
+
+        if !T::accepts(column.type_oid()) {
+            return Err(Error::Conversion(format!(
+                "Cannot decode column \"{}\" of type OID {} into {}",
+                column.name(),
+                column.type_oid(),
+                std::any::type_name::<T>(),
+            )));
+        }
+
+        T::from_sql_nullable(column.type_oid(), column.format(), self.values[idx].as_deref())
+    }
+
+    /// Raw wire value of a column, `None` for NULL.
+    pub fn get_bytes(&self, idx: impl RowIndex) -> Result<Option<&[u8]>, Error> {
+        let idx = self.position(idx)?;
+
+        Ok(self.values[idx].as_deref())
+    }
+
+    /// Text value of a column, `None` for NULL.
+    pub fn get_str(&self, idx: impl RowIndex) -> Result<Option<&str>, Error> {
+        match self.get_bytes(idx)? {
+            Some(bytes) => std::str::from_utf8(bytes)
+                .map(Some)
+                .map_err(|_| Error::Conversion("Column value is not valid UTF-8".to_string())),
+            None => Ok(None),
+        }
+    }
+
+    fn position(&self, idx: impl RowIndex) -> Result<usize, Error> {
+        idx.index(&self.columns)
+            .filter(|idx| *idx < self.values.len())
+            .ok_or_else(|| Error::Column("No such column in row".to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::messages::back::BackMessage;
+
+    fn test_row() -> Row {
+        let mut body = 2_i16.to_be_bytes().to_vec();
+        for (name, type_oid) in [("id", 23_i32), ("note", 25_i32)] {
+            body.extend(name.as_bytes());
+            body.push(0);
+            body.extend(&0_i32.to_be_bytes());
+            body.extend(&0_i16.to_be_bytes());
+            body.extend(&type_oid.to_be_bytes());
+            body.extend(&(-1_i16).to_be_bytes());
+            body.extend(&(-1_i32).to_be_bytes());
+            body.extend(&0_i16.to_be_bytes());
+        }
+        let columns = BackMessage::from_parts(b'T', body)
+            .row_description()
+            .unwrap();
+
+        Row::new(Arc::new(columns), vec![Some(b"1".to_vec()), None])
+    }
+
+    #[test]
+    fn test_get_by_index() {
+        let row = test_row();
+
+        assert_eq!(row.len(), 2);
+        assert_eq!(row.get_str(0).unwrap(), Some("1"));
+        assert_eq!(row.get_str(1).unwrap(), None);
+        assert!(row.get_str(2).is_err());
+    }
+
+    #[test]
+    fn test_get_by_name() {
+        let row = test_row();
+
+        assert_eq!(row.get_bytes("id").unwrap(), Some(&b"1"[..]));
+        assert_eq!(row.get_bytes("note").unwrap(), None);
+        assert!(row.get_bytes("missing").is_err());
+    }
+
+    #[test]
+    fn test_get_typed() {
+        let row = test_row();
+
+        assert_eq!(row.get::<i32>(0).unwrap(), 1);
+        assert_eq!(row.get::<Option<String>>("note").unwrap(), None);
+        assert!(row.get::<String>("note").is_err());
+        assert!(row.get::<String>("id").is_err());
+    }
+}
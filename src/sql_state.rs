@@ -0,0 +1,320 @@
+macro_rules! sql_states {
+    ($($variant:ident, $konst:ident, $code:literal;)*) => {
+        /// SQLSTATE codes from Appendix A of the PostgreSQL documentation.
+        ///
+        /// Every code is available both as a variant and as an associated
+        /// constant, so `SqlState::UNIQUE_VIOLATION` can be used in patterns.
+        /// Codes unknown to this crate are kept in [`SqlState::Other`].
+        #[derive(Debug, Clone, PartialEq, Eq, Hash)]
+        pub enum SqlState {
+            $($variant,)*
+            Other(String),
+        }
+
+        impl SqlState {
+            $(pub const $konst: SqlState = SqlState::$variant;)*
+
+            pub fn from_code(code: &str) -> SqlState {
+                match code {
+                    $($code => SqlState::$variant,)*
+                    other => SqlState::Other(other.to_string()),
+                }
+            }
+
+            pub fn code(&self) -> &str {
+                match self {
+                    $(SqlState::$variant => $code,)*
+                    SqlState::Other(code) => code,
+                }
+            }
+        }
+    };
+}
+
+sql_states! {
+    SuccessfulCompletion,                            SUCCESSFUL_COMPLETION,                                "00000";
+    Warning,                                         WARNING,                                              "01000";
+    WarningDynamicResultSetsReturned,                WARNING_DYNAMIC_RESULT_SETS_RETURNED,                 "0100C";
+    WarningImplicitZeroBitPadding,                   WARNING_IMPLICIT_ZERO_BIT_PADDING,                    "01008";
+    WarningNullValueEliminatedInSetFunction,         WARNING_NULL_VALUE_ELIMINATED_IN_SET_FUNCTION,        "01003";
+    WarningPrivilegeNotGranted,                      WARNING_PRIVILEGE_NOT_GRANTED,                        "01007";
+    WarningPrivilegeNotRevoked,                      WARNING_PRIVILEGE_NOT_REVOKED,                        "01006";
+    WarningStringDataRightTruncation,                WARNING_STRING_DATA_RIGHT_TRUNCATION,                 "01004";
+    WarningDeprecatedFeature,                        WARNING_DEPRECATED_FEATURE,                           "01P01";
+    NoData,                                          NO_DATA,                                              "02000";
+    NoAdditionalDynamicResultSetsReturned,           NO_ADDITIONAL_DYNAMIC_RESULT_SETS_RETURNED,           "02001";
+    SqlStatementNotYetComplete,                      SQL_STATEMENT_NOT_YET_COMPLETE,                       "03000";
+    ConnectionException,                             CONNECTION_EXCEPTION,                                 "08000";
+    ConnectionDoesNotExist,                          CONNECTION_DOES_NOT_EXIST,                            "08003";
+    ConnectionFailure,                               CONNECTION_FAILURE,                                   "08006";
+    SqlclientUnableToEstablishSqlconnection,         SQLCLIENT_UNABLE_TO_ESTABLISH_SQLCONNECTION,          "08001";
+    SqlserverRejectedEstablishmentOfSqlconnection,   SQLSERVER_REJECTED_ESTABLISHMENT_OF_SQLCONNECTION,    "08004";
+    TransactionResolutionUnknown,                    TRANSACTION_RESOLUTION_UNKNOWN,                       "08007";
+    ProtocolViolation,                               PROTOCOL_VIOLATION,                                   "08P01";
+    TriggeredActionException,                        TRIGGERED_ACTION_EXCEPTION,                           "09000";
+    FeatureNotSupported,                             FEATURE_NOT_SUPPORTED,                                "0A000";
+    InvalidTransactionInitiation,                    INVALID_TRANSACTION_INITIATION,                       "0B000";
+    LocatorException,                                LOCATOR_EXCEPTION,                                    "0F000";
+    LEInvalidSpecification,                          L_E_INVALID_SPECIFICATION,                            "0F001";
+    InvalidGrantor,                                  INVALID_GRANTOR,                                      "0L000";
+    InvalidGrantOperation,                           INVALID_GRANT_OPERATION,                              "0LP01";
+    InvalidRoleSpecification,                        INVALID_ROLE_SPECIFICATION,                           "0P000";
+    DiagnosticsException,                            DIAGNOSTICS_EXCEPTION,                                "0Z000";
+    StackedDiagnosticsAccessedWithoutActiveHandler,  STACKED_DIAGNOSTICS_ACCESSED_WITHOUT_ACTIVE_HANDLER,  "0Z002";
+    CaseNotFound,                                    CASE_NOT_FOUND,                                       "20000";
+    CardinalityViolation,                            CARDINALITY_VIOLATION,                                "21000";
+    DataException,                                   DATA_EXCEPTION,                                       "22000";
+    ArraySubscriptError,                             ARRAY_SUBSCRIPT_ERROR,                                "2202E";
+    CharacterNotInRepertoire,                        CHARACTER_NOT_IN_REPERTOIRE,                          "22021";
+    DatetimeFieldOverflow,                           DATETIME_FIELD_OVERFLOW,                              "22008";
+    DivisionByZero,                                  DIVISION_BY_ZERO,                                     "22012";
+    ErrorInAssignment,                               ERROR_IN_ASSIGNMENT,                                  "22005";
+    EscapeCharacterConflict,                         ESCAPE_CHARACTER_CONFLICT,                            "2200B";
+    IndicatorOverflow,                               INDICATOR_OVERFLOW,                                   "22022";
+    IntervalFieldOverflow,                           INTERVAL_FIELD_OVERFLOW,                              "22015";
+    InvalidArgumentForLog,                           INVALID_ARGUMENT_FOR_LOG,                             "2201E";
+    InvalidArgumentForNtile,                         INVALID_ARGUMENT_FOR_NTILE,                           "22014";
+    InvalidArgumentForNthValue,                      INVALID_ARGUMENT_FOR_NTH_VALUE,                       "22016";
+    InvalidArgumentForPowerFunction,                 INVALID_ARGUMENT_FOR_POWER_FUNCTION,                  "2201F";
+    InvalidArgumentForWidthBucketFunction,           INVALID_ARGUMENT_FOR_WIDTH_BUCKET_FUNCTION,           "2201G";
+    InvalidCharacterValueForCast,                    INVALID_CHARACTER_VALUE_FOR_CAST,                     "22018";
+    InvalidDatetimeFormat,                           INVALID_DATETIME_FORMAT,                              "22007";
+    InvalidEscapeCharacter,                          INVALID_ESCAPE_CHARACTER,                             "22019";
+    InvalidEscapeOctet,                              INVALID_ESCAPE_OCTET,                                 "2200D";
+    InvalidEscapeSequence,                           INVALID_ESCAPE_SEQUENCE,                              "22025";
+    NonstandardUseOfEscapeCharacter,                 NONSTANDARD_USE_OF_ESCAPE_CHARACTER,                  "22P06";
+    InvalidIndicatorParameterValue,                  INVALID_INDICATOR_PARAMETER_VALUE,                    "22010";
+    InvalidParameterValue,                           INVALID_PARAMETER_VALUE,                              "22023";
+    InvalidPrecedingOrFollowingSize,                 INVALID_PRECEDING_OR_FOLLOWING_SIZE,                  "22013";
+    InvalidRegularExpression,                        INVALID_REGULAR_EXPRESSION,                           "2201B";
+    InvalidRowCountInLimitClause,                    INVALID_ROW_COUNT_IN_LIMIT_CLAUSE,                    "2201W";
+    InvalidRowCountInResultOffsetClause,             INVALID_ROW_COUNT_IN_RESULT_OFFSET_CLAUSE,            "2201X";
+    InvalidTablesampleArgument,                      INVALID_TABLESAMPLE_ARGUMENT,                         "2202H";
+    InvalidTablesampleRepeat,                        INVALID_TABLESAMPLE_REPEAT,                           "2202G";
+    InvalidTimeZoneDisplacementValue,                INVALID_TIME_ZONE_DISPLACEMENT_VALUE,                 "22009";
+    InvalidUseOfEscapeCharacter,                     INVALID_USE_OF_ESCAPE_CHARACTER,                      "2200C";
+    MostSpecificTypeMismatch,                        MOST_SPECIFIC_TYPE_MISMATCH,                          "2200G";
+    NullValueNotAllowed,                             NULL_VALUE_NOT_ALLOWED,                               "22004";
+    NullValueNoIndicatorParameter,                   NULL_VALUE_NO_INDICATOR_PARAMETER,                    "22002";
+    NumericValueOutOfRange,                          NUMERIC_VALUE_OUT_OF_RANGE,                           "22003";
+    SequenceGeneratorLimitExceeded,                  SEQUENCE_GENERATOR_LIMIT_EXCEEDED,                    "2200H";
+    StringDataLengthMismatch,                        STRING_DATA_LENGTH_MISMATCH,                          "22026";
+    StringDataRightTruncation,                       STRING_DATA_RIGHT_TRUNCATION,                         "22001";
+    SubstringError,                                  SUBSTRING_ERROR,                                      "22011";
+    TrimError,                                       TRIM_ERROR,                                           "22027";
+    UnterminatedCString,                             UNTERMINATED_C_STRING,                                "22024";
+    ZeroLengthCharacterString,                       ZERO_LENGTH_CHARACTER_STRING,                         "2200F";
+    FloatingPointException,                          FLOATING_POINT_EXCEPTION,                             "22P01";
+    InvalidTextRepresentation,                       INVALID_TEXT_REPRESENTATION,                          "22P02";
+    InvalidBinaryRepresentation,                     INVALID_BINARY_REPRESENTATION,                        "22P03";
+    BadCopyFileFormat,                               BAD_COPY_FILE_FORMAT,                                 "22P04";
+    UntranslatableCharacter,                         UNTRANSLATABLE_CHARACTER,                             "22P05";
+    NotAnXmlDocument,                                NOT_AN_XML_DOCUMENT,                                  "2200L";
+    InvalidXmlDocument,                              INVALID_XML_DOCUMENT,                                 "2200M";
+    InvalidXmlContent,                               INVALID_XML_CONTENT,                                  "2200N";
+    InvalidXmlComment,                               INVALID_XML_COMMENT,                                  "2200S";
+    InvalidXmlProcessingInstruction,                 INVALID_XML_PROCESSING_INSTRUCTION,                   "2200T";
+    DuplicateJsonObjectKeyValue,                     DUPLICATE_JSON_OBJECT_KEY_VALUE,                      "22030";
+    InvalidArgumentForSqlJsonDatetimeFunction,       INVALID_ARGUMENT_FOR_SQL_JSON_DATETIME_FUNCTION,      "22031";
+    InvalidJsonText,                                 INVALID_JSON_TEXT,                                    "22032";
+    InvalidSqlJsonSubscript,                         INVALID_SQL_JSON_SUBSCRIPT,                           "22033";
+    MoreThanOneSqlJsonItem,                          MORE_THAN_ONE_SQL_JSON_ITEM,                          "22034";
+    NoSqlJsonItem,                                   NO_SQL_JSON_ITEM,                                     "22035";
+    NonNumericSqlJsonItem,                           NON_NUMERIC_SQL_JSON_ITEM,                            "22036";
+    NonUniqueKeysInAJsonObject,                      NON_UNIQUE_KEYS_IN_A_JSON_OBJECT,                     "22037";
+    SingletonSqlJsonItemRequired,                    SINGLETON_SQL_JSON_ITEM_REQUIRED,                     "22038";
+    SqlJsonArrayNotFound,                            SQL_JSON_ARRAY_NOT_FOUND,                             "22039";
+    SqlJsonMemberNotFound,                           SQL_JSON_MEMBER_NOT_FOUND,                            "2203A";
+    SqlJsonNumberNotFound,                           SQL_JSON_NUMBER_NOT_FOUND,                            "2203B";
+    SqlJsonObjectNotFound,                           SQL_JSON_OBJECT_NOT_FOUND,                            "2203C";
+    TooManyJsonArrayElements,                        TOO_MANY_JSON_ARRAY_ELEMENTS,                         "2203D";
+    TooManyJsonObjectMembers,                        TOO_MANY_JSON_OBJECT_MEMBERS,                         "2203E";
+    SqlJsonScalarRequired,                           SQL_JSON_SCALAR_REQUIRED,                             "2203F";
+    SqlJsonItemCannotBeCastToTargetType,             SQL_JSON_ITEM_CANNOT_BE_CAST_TO_TARGET_TYPE,          "2203G";
+    IntegrityConstraintViolation,                    INTEGRITY_CONSTRAINT_VIOLATION,                       "23000";
+    RestrictViolation,                               RESTRICT_VIOLATION,                                   "23001";
+    NotNullViolation,                                NOT_NULL_VIOLATION,                                   "23502";
+    ForeignKeyViolation,                             FOREIGN_KEY_VIOLATION,                                "23503";
+    UniqueViolation,                                 UNIQUE_VIOLATION,                                     "23505";
+    CheckViolation,                                  CHECK_VIOLATION,                                      "23514";
+    ExclusionViolation,                              EXCLUSION_VIOLATION,                                  "23P01";
+    InvalidCursorState,                              INVALID_CURSOR_STATE,                                 "24000";
+    InvalidTransactionState,                         INVALID_TRANSACTION_STATE,                            "25000";
+    ActiveSqlTransaction,                            ACTIVE_SQL_TRANSACTION,                               "25001";
+    BranchTransactionAlreadyActive,                  BRANCH_TRANSACTION_ALREADY_ACTIVE,                    "25002";
+    HeldCursorRequiresSameIsolationLevel,            HELD_CURSOR_REQUIRES_SAME_ISOLATION_LEVEL,            "25008";
+    InappropriateAccessModeForBranchTransaction,     INAPPROPRIATE_ACCESS_MODE_FOR_BRANCH_TRANSACTION,     "25003";
+    InappropriateIsolationLevelForBranchTransaction, INAPPROPRIATE_ISOLATION_LEVEL_FOR_BRANCH_TRANSACTION, "25004";
+    NoActiveSqlTransactionForBranchTransaction,      NO_ACTIVE_SQL_TRANSACTION_FOR_BRANCH_TRANSACTION,     "25005";
+    ReadOnlySqlTransaction,                          READ_ONLY_SQL_TRANSACTION,                            "25006";
+    SchemaAndDataStatementMixingNotSupported,        SCHEMA_AND_DATA_STATEMENT_MIXING_NOT_SUPPORTED,       "25007";
+    NoActiveSqlTransaction,                          NO_ACTIVE_SQL_TRANSACTION,                            "25P01";
+    InFailedSqlTransaction,                          IN_FAILED_SQL_TRANSACTION,                            "25P02";
+    IdleInTransactionSessionTimeout,                 IDLE_IN_TRANSACTION_SESSION_TIMEOUT,                  "25P03";
+    InvalidSqlStatementName,                         INVALID_SQL_STATEMENT_NAME,                           "26000";
+    TriggeredDataChangeViolation,                    TRIGGERED_DATA_CHANGE_VIOLATION,                      "27000";
+    InvalidAuthorizationSpecification,               INVALID_AUTHORIZATION_SPECIFICATION,                  "28000";
+    InvalidPassword,                                 INVALID_PASSWORD,                                     "28P01";
+    DependentPrivilegeDescriptorsStillExist,         DEPENDENT_PRIVILEGE_DESCRIPTORS_STILL_EXIST,          "2B000";
+    DependentObjectsStillExist,                      DEPENDENT_OBJECTS_STILL_EXIST,                        "2BP01";
+    InvalidTransactionTermination,                   INVALID_TRANSACTION_TERMINATION,                      "2D000";
+    SqlRoutineException,                             SQL_ROUTINE_EXCEPTION,                                "2F000";
+    SREFunctionExecutedNoReturnStatement,            S_R_E_FUNCTION_EXECUTED_NO_RETURN_STATEMENT,          "2F005";
+    SREModifyingSqlDataNotPermitted,                 S_R_E_MODIFYING_SQL_DATA_NOT_PERMITTED,               "2F002";
+    SREProhibitedSqlStatementAttempted,              S_R_E_PROHIBITED_SQL_STATEMENT_ATTEMPTED,             "2F003";
+    SREReadingSqlDataNotPermitted,                   S_R_E_READING_SQL_DATA_NOT_PERMITTED,                 "2F004";
+    InvalidCursorName,                               INVALID_CURSOR_NAME,                                  "34000";
+    ExternalRoutineException,                        EXTERNAL_ROUTINE_EXCEPTION,                           "38000";
+    EREContainingSqlNotPermitted,                    E_R_E_CONTAINING_SQL_NOT_PERMITTED,                   "38001";
+    EREModifyingSqlDataNotPermitted,                 E_R_E_MODIFYING_SQL_DATA_NOT_PERMITTED,               "38002";
+    EREProhibitedSqlStatementAttempted,              E_R_E_PROHIBITED_SQL_STATEMENT_ATTEMPTED,             "38003";
+    EREReadingSqlDataNotPermitted,                   E_R_E_READING_SQL_DATA_NOT_PERMITTED,                 "38004";
+    ExternalRoutineInvocationException,              EXTERNAL_ROUTINE_INVOCATION_EXCEPTION,                "39000";
+    ERIEInvalidSqlstateReturned,                     E_R_I_E_INVALID_SQLSTATE_RETURNED,                    "39001";
+    ERIENullValueNotAllowed,                         E_R_I_E_NULL_VALUE_NOT_ALLOWED,                       "39004";
+    ERIETriggerProtocolViolated,                     E_R_I_E_TRIGGER_PROTOCOL_VIOLATED,                    "39P01";
+    ERIESrfProtocolViolated,                         E_R_I_E_SRF_PROTOCOL_VIOLATED,                        "39P02";
+    ERIEEventTriggerProtocolViolated,                E_R_I_E_EVENT_TRIGGER_PROTOCOL_VIOLATED,              "39P03";
+    SavepointException,                              SAVEPOINT_EXCEPTION,                                  "3B000";
+    SEInvalidSpecification,                          S_E_INVALID_SPECIFICATION,                            "3B001";
+    InvalidCatalogName,                              INVALID_CATALOG_NAME,                                 "3D000";
+    InvalidSchemaName,                               INVALID_SCHEMA_NAME,                                  "3F000";
+    TransactionRollback,                             TRANSACTION_ROLLBACK,                                 "40000";
+    TRIntegrityConstraintViolation,                  T_R_INTEGRITY_CONSTRAINT_VIOLATION,                   "40002";
+    TRSerializationFailure,                          T_R_SERIALIZATION_FAILURE,                            "40001";
+    TRStatementCompletionUnknown,                    T_R_STATEMENT_COMPLETION_UNKNOWN,                     "40003";
+    TRDeadlockDetected,                              T_R_DEADLOCK_DETECTED,                                "40P01";
+    SyntaxErrorOrAccessRuleViolation,                SYNTAX_ERROR_OR_ACCESS_RULE_VIOLATION,                "42000";
+    SyntaxError,                                     SYNTAX_ERROR,                                         "42601";
+    InsufficientPrivilege,                           INSUFFICIENT_PRIVILEGE,                               "42501";
+    CannotCoerce,                                    CANNOT_COERCE,                                        "42846";
+    GroupingError,                                   GROUPING_ERROR,                                       "42803";
+    WindowingError,                                  WINDOWING_ERROR,                                      "42P20";
+    InvalidRecursion,                                INVALID_RECURSION,                                    "42P19";
+    InvalidForeignKey,                               INVALID_FOREIGN_KEY,                                  "42830";
+    InvalidName,                                     INVALID_NAME,                                         "42602";
+    NameTooLong,                                     NAME_TOO_LONG,                                        "42622";
+    ReservedName,                                    RESERVED_NAME,                                        "42939";
+    DatatypeMismatch,                                DATATYPE_MISMATCH,                                    "42804";
+    IndeterminateDatatype,                           INDETERMINATE_DATATYPE,                               "42P18";
+    CollationMismatch,                               COLLATION_MISMATCH,                                   "42P21";
+    IndeterminateCollation,                          INDETERMINATE_COLLATION,                              "42P22";
+    WrongObjectType,                                 WRONG_OBJECT_TYPE,                                    "42809";
+    GeneratedAlways,                                 GENERATED_ALWAYS,                                     "428C9";
+    UndefinedColumn,                                 UNDEFINED_COLUMN,                                     "42703";
+    UndefinedFunction,                               UNDEFINED_FUNCTION,                                   "42883";
+    UndefinedTable,                                  UNDEFINED_TABLE,                                      "42P01";
+    UndefinedParameter,                              UNDEFINED_PARAMETER,                                  "42P02";
+    UndefinedObject,                                 UNDEFINED_OBJECT,                                     "42704";
+    DuplicateColumn,                                 DUPLICATE_COLUMN,                                     "42701";
+    DuplicateCursor,                                 DUPLICATE_CURSOR,                                     "42P03";
+    DuplicateDatabase,                               DUPLICATE_DATABASE,                                   "42P04";
+    DuplicateFunction,                               DUPLICATE_FUNCTION,                                   "42723";
+    DuplicatePstatement,                             DUPLICATE_PSTATEMENT,                                 "42P05";
+    DuplicateSchema,                                 DUPLICATE_SCHEMA,                                     "42P06";
+    DuplicateTable,                                  DUPLICATE_TABLE,                                      "42P07";
+    DuplicateAlias,                                  DUPLICATE_ALIAS,                                      "42712";
+    DuplicateObject,                                 DUPLICATE_OBJECT,                                     "42710";
+    AmbiguousColumn,                                 AMBIGUOUS_COLUMN,                                     "42702";
+    AmbiguousFunction,                               AMBIGUOUS_FUNCTION,                                   "42725";
+    AmbiguousParameter,                              AMBIGUOUS_PARAMETER,                                  "42P08";
+    AmbiguousAlias,                                  AMBIGUOUS_ALIAS,                                      "42P09";
+    InvalidColumnReference,                          INVALID_COLUMN_REFERENCE,                             "42P10";
+    InvalidColumnDefinition,                         INVALID_COLUMN_DEFINITION,                            "42611";
+    InvalidCursorDefinition,                         INVALID_CURSOR_DEFINITION,                            "42P11";
+    InvalidDatabaseDefinition,                       INVALID_DATABASE_DEFINITION,                          "42P12";
+    InvalidFunctionDefinition,                       INVALID_FUNCTION_DEFINITION,                          "42P13";
+    InvalidPstatementDefinition,                     INVALID_PSTATEMENT_DEFINITION,                        "42P14";
+    InvalidSchemaDefinition,                         INVALID_SCHEMA_DEFINITION,                            "42P15";
+    InvalidTableDefinition,                          INVALID_TABLE_DEFINITION,                             "42P16";
+    InvalidObjectDefinition,                         INVALID_OBJECT_DEFINITION,                            "42P17";
+    WithCheckOptionViolation,                        WITH_CHECK_OPTION_VIOLATION,                          "44000";
+    InsufficientResources,                           INSUFFICIENT_RESOURCES,                               "53000";
+    DiskFull,                                        DISK_FULL,                                            "53100";
+    OutOfMemory,                                     OUT_OF_MEMORY,                                        "53200";
+    TooManyConnections,                              TOO_MANY_CONNECTIONS,                                 "53300";
+    ConfigurationLimitExceeded,                      CONFIGURATION_LIMIT_EXCEEDED,                         "53400";
+    ProgramLimitExceeded,                            PROGRAM_LIMIT_EXCEEDED,                               "54000";
+    StatementTooComplex,                             STATEMENT_TOO_COMPLEX,                                "54001";
+    TooManyColumns,                                  TOO_MANY_COLUMNS,                                     "54011";
+    TooManyArguments,                                TOO_MANY_ARGUMENTS,                                   "54023";
+    ObjectNotInPrerequisiteState,                    OBJECT_NOT_IN_PREREQUISITE_STATE,                     "55000";
+    ObjectInUse,                                     OBJECT_IN_USE,                                        "55006";
+    CantChangeRuntimeParam,                          CANT_CHANGE_RUNTIME_PARAM,                            "55P02";
+    LockNotAvailable,                                LOCK_NOT_AVAILABLE,                                   "55P03";
+    UnsafeNewEnumValueUsage,                         UNSAFE_NEW_ENUM_VALUE_USAGE,                          "55P04";
+    OperatorIntervention,                            OPERATOR_INTERVENTION,                                "57000";
+    QueryCanceled,                                   QUERY_CANCELED,                                       "57014";
+    AdminShutdown,                                   ADMIN_SHUTDOWN,                                       "57P01";
+    CrashShutdown,                                   CRASH_SHUTDOWN,                                       "57P02";
+    CannotConnectNow,                                CANNOT_CONNECT_NOW,                                   "57P03";
+    DatabaseDropped,                                 DATABASE_DROPPED,                                     "57P04";
+    IdleSessionTimeout,                              IDLE_SESSION_TIMEOUT,                                 "57P05";
+    SystemError,                                     SYSTEM_ERROR,                                         "58000";
+    IoError,                                         IO_ERROR,                                             "58030";
+    UndefinedFile,                                   UNDEFINED_FILE,                                       "58P01";
+    DuplicateFile,                                   DUPLICATE_FILE,                                       "58P02";
+    SnapshotTooOld,                                  SNAPSHOT_TOO_OLD,                                     "72000";
+    ConfigFileError,                                 CONFIG_FILE_ERROR,                                    "F0000";
+    LockFileExists,                                  LOCK_FILE_EXISTS,                                     "F0001";
+    FdwError,                                        FDW_ERROR,                                            "HV000";
+    FdwColumnNameNotFound,                           FDW_COLUMN_NAME_NOT_FOUND,                            "HV005";
+    FdwDynamicParameterValueNeeded,                  FDW_DYNAMIC_PARAMETER_VALUE_NEEDED,                   "HV002";
+    FdwFunctionSequenceError,                        FDW_FUNCTION_SEQUENCE_ERROR,                          "HV010";
+    FdwInconsistentDescriptorInformation,            FDW_INCONSISTENT_DESCRIPTOR_INFORMATION,              "HV021";
+    FdwInvalidAttributeValue,                        FDW_INVALID_ATTRIBUTE_VALUE,                          "HV024";
+    FdwInvalidColumnName,                            FDW_INVALID_COLUMN_NAME,                              "HV007";
+    FdwInvalidColumnNumber,                          FDW_INVALID_COLUMN_NUMBER,                            "HV008";
+    FdwInvalidDataType,                              FDW_INVALID_DATA_TYPE,                                "HV004";
+    FdwInvalidDataTypeDescriptors,                   FDW_INVALID_DATA_TYPE_DESCRIPTORS,                    "HV006";
+    FdwInvalidDescriptorFieldIdentifier,             FDW_INVALID_DESCRIPTOR_FIELD_IDENTIFIER,              "HV091";
+    FdwInvalidHandle,                                FDW_INVALID_HANDLE,                                   "HV00B";
+    FdwInvalidOptionIndex,                           FDW_INVALID_OPTION_INDEX,                             "HV00C";
+    FdwInvalidOptionName,                            FDW_INVALID_OPTION_NAME,                              "HV00D";
+    FdwInvalidStringLengthOrBufferLength,            FDW_INVALID_STRING_LENGTH_OR_BUFFER_LENGTH,           "HV090";
+    FdwInvalidStringFormat,                          FDW_INVALID_STRING_FORMAT,                            "HV00A";
+    FdwInvalidUseOfNullPointer,                      FDW_INVALID_USE_OF_NULL_POINTER,                      "HV009";
+    FdwTooManyHandles,                               FDW_TOO_MANY_HANDLES,                                 "HV014";
+    FdwOutOfMemory,                                  FDW_OUT_OF_MEMORY,                                    "HV001";
+    FdwNoSchemas,                                    FDW_NO_SCHEMAS,                                       "HV00P";
+    FdwOptionNameNotFound,                           FDW_OPTION_NAME_NOT_FOUND,                            "HV00J";
+    FdwReplyHandle,                                  FDW_REPLY_HANDLE,                                     "HV00K";
+    FdwSchemaNotFound,                               FDW_SCHEMA_NOT_FOUND,                                 "HV00Q";
+    FdwTableNotFound,                                FDW_TABLE_NOT_FOUND,                                  "HV00R";
+    FdwUnableToCreateExecution,                      FDW_UNABLE_TO_CREATE_EXECUTION,                       "HV00L";
+    FdwUnableToCreateReply,                          FDW_UNABLE_TO_CREATE_REPLY,                           "HV00M";
+    FdwUnableToEstablishConnection,                  FDW_UNABLE_TO_ESTABLISH_CONNECTION,                   "HV00N";
+    PlpgsqlError,                                    PLPGSQL_ERROR,                                        "P0000";
+    RaiseException,                                  RAISE_EXCEPTION,                                      "P0001";
+    NoDataFound,                                     NO_DATA_FOUND,                                        "P0002";
+    TooManyRows,                                     TOO_MANY_ROWS,                                        "P0003";
+    AssertFailure,                                   ASSERT_FAILURE,                                       "P0004";
+    InternalError,                                   INTERNAL_ERROR,                                       "XX000";
+    DataCorrupted,                                   DATA_CORRUPTED,                                       "XX001";
+    IndexCorrupted,                                  INDEX_CORRUPTED,                                      "XX002";
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_code() {
+        assert_eq!(SqlState::from_code("23505"), SqlState::UNIQUE_VIOLATION);
+        assert_eq!(SqlState::from_code("40001"), SqlState::T_R_SERIALIZATION_FAILURE);
+        assert_eq!(SqlState::from_code("XXYYZ"), SqlState::Other("XXYYZ".to_string()));
+    }
+
+    #[test]
+    fn test_code() {
+        assert_eq!(SqlState::SYNTAX_ERROR.code(), "42601");
+        assert_eq!(SqlState::Other("XXYYZ".to_string()).code(), "XXYYZ");
+    }
+
+    #[test]
+    fn test_match() {
+        // The associated constants are usable as patterns.
+        assert!(matches!(SqlState::from_code("23505"), SqlState::UNIQUE_VIOLATION));
+        assert!(!matches!(SqlState::from_code("40P01"), SqlState::T_R_SERIALIZATION_FAILURE));
+    }
+}
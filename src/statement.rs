@@ -0,0 +1,51 @@
+use std::sync::Arc;
+use crate::messages::back::FieldDescription;
+
+/// A server-side prepared statement created by `Client::prepare`.
+///
+/// Statements live in the client's statement cache and are closed on the
+/// server once the cache evicts them, so a `Statement` should not be kept
+/// around much longer than the statements prepared after it.
+#[derive(Debug, Clone)]
+pub struct Statement {
+    name: String,
+    sql: String,
+    param_types: Vec<u32>,
+    columns: Arc<Vec<FieldDescription>>,
+}
+
+impl Statement {
+    pub(crate) fn new(
+        name: String,
+        sql: String,
+        param_types: Vec<u32>,
+        columns: Arc<Vec<FieldDescription>>,
+    ) -> Statement {
+        Statement { name, sql, param_types, columns }
+    }
+
+    #[inline]
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    #[inline]
+    pub fn sql(&self) -> &str {
+        &self.sql
+    }
+
+    #[inline]
+    pub fn param_types(&self) -> &[u32] {
+        &self.param_types
+    }
+
+    #[inline]
+    pub fn columns(&self) -> &[FieldDescription] {
+        &self.columns
+    }
+
+    #[inline]
+    pub(crate) fn shared_columns(&self) -> Arc<Vec<FieldDescription>> {
+        self.columns.clone()
+    }
+}
@@ -1,11 +1,15 @@
-use std::error::Error;
+use std::error::Error as StdError;
 use pg_rs::protocol::connection::Connection;
 use pg_rs::protocol::conn_params::ConnParamsBuilder;
+#[cfg(not(feature = "tls"))]
+use pg_rs::protocol::conn_params::SslMode;
+#[cfg(not(feature = "tls"))]
+use pg_rs::Error;
 use pg_rs::messages::back::BackTypes;
 use pg_rs::messages::front_builder::StartupMessageBuilder;
 
 #[test]
-fn test_conn_new() -> Result<(), Box<dyn Error>> {
+fn test_conn_new() -> Result<(), Box<dyn StdError>> {
     let params = ConnParamsBuilder::new()
         .build();
     Connection::new(&params)?;
@@ -13,7 +17,7 @@ fn test_conn_new() -> Result<(), Box<dyn Error>> {
 }
 
 #[test]
-fn test_conn_auth() -> Result<(), Box<dyn Error>> {
+fn test_conn_auth() -> Result<(), Box<dyn StdError>> {
     let params = ConnParamsBuilder::new()
         .build();
     let mut conn = Connection::new(&params)?;
@@ -27,3 +31,11 @@ fn test_conn_auth() -> Result<(), Box<dyn Error>> {
     }
 }
 
+#[cfg(not(feature = "tls"))]
+#[test]
+fn test_conn_require_tls_without_feature() {
+    let params = ConnParamsBuilder::new()
+        .ssl_mode(SslMode::Require)
+        .build();
+    assert!(matches!(Connection::new(&params), Err(Error::Config(_))));
+}
@@ -0,0 +1,287 @@
+#![cfg(feature = "tls")]
+//! TLS negotiation against an in-process server that speaks just enough
+//! of the protocol to accept a connection and answer simple queries.
+//!
+//! The certificates in `fixtures/tls` were made with the openssl CLI:
+//! `ca.crt` signs `server.crt` (for 127.0.0.1), `wrong-name.crt` (for
+//! db.example.com) and `client.crt`, while `other-ca.crt` signs nothing.
+
+use std::error::Error as StdError;
+use std::fs::File;
+use std::io::{BufReader, Read, Write};
+use std::net::TcpListener;
+use std::sync::Arc;
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+use rustls::crypto::ring;
+use rustls::server::WebPkiClientVerifier;
+use rustls::{RootCertStore, ServerConfig, ServerConnection, StreamOwned};
+use pg_rs::Error;
+use pg_rs::client::Client;
+use pg_rs::client_builder::ClientBuilder;
+use pg_rs::protocol::conn_params::SslMode;
+
+type ServerResult = Result<bool, Box<dyn StdError + Send + Sync>>;
+
+const SSL_REQUEST: [u8; 8] = [0, 0, 0, 8, 4, 210, 22, 47];
+
+macro_rules! fixture {
+    ($name:literal) => {
+        concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/tls/", $name)
+    };
+}
+
+struct FakeServer {
+    port: u32,
+    handle: JoinHandle<ServerResult>,
+}
+
+impl FakeServer {
+    /// Accepts one connection, answers its SSLRequest with `answer` and,
+    /// on `S`, presents the certificate `cert`.
+    fn start(answer: u8, cert: &'static str, verify_client: bool) -> FakeServer {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port() as u32;
+
+        let handle = thread::spawn(move || -> ServerResult {
+            let (mut tcp, _) = listener.accept()?;
+            let mut request = [0; 8];
+            tcp.read_exact(&mut request)?;
+            assert_eq!(request, SSL_REQUEST);
+            tcp.write_all(&[answer])?;
+
+            if answer != b'S' {
+                serve(&mut tcp)?;
+                return Ok(false);
+            }
+
+            let conn = ServerConnection::new(Arc::new(server_config(cert, verify_client)?))?;
+            let mut tls = StreamOwned::new(conn, tcp);
+            serve(&mut tls)?;
+            Ok(tls.conn.peer_certificates().is_some())
+        });
+
+        FakeServer { port, handle }
+    }
+
+    fn client(&self) -> ClientBuilder<'static> {
+        ClientBuilder::new()
+            .host("127.0.0.1")
+            .port(self.port)
+    }
+
+    /// Whether the client presented a certificate.
+    fn join(self) -> ServerResult {
+        self.handle.join().unwrap()
+    }
+}
+
+fn server_config(cert: &str, verify_client: bool) -> Result<ServerConfig, Box<dyn StdError + Send + Sync>> {
+    let provider = Arc::new(ring::default_provider());
+    let certs = rustls_pemfile::certs(&mut BufReader::new(File::open(format!("{}.crt", cert))?))
+        .collect::<Result<Vec<_>, _>>()?;
+    let key = rustls_pemfile::private_key(&mut BufReader::new(File::open(format!("{}.key", cert))?))?
+        .ok_or("missing key")?;
+
+    let builder = ServerConfig::builder_with_provider(provider.clone())
+        .with_safe_default_protocol_versions()?;
+    let builder = if verify_client {
+        let mut roots = RootCertStore::empty();
+        for cert in rustls_pemfile::certs(&mut BufReader::new(File::open(fixture!("ca.crt"))?)) {
+            roots.add(cert?)?;
+        }
+        builder.with_client_cert_verifier(
+            WebPkiClientVerifier::builder_with_provider(Arc::new(roots), provider).build()?,
+        )
+    } else {
+        builder.with_no_client_auth()
+    };
+
+    Ok(builder.with_single_cert(certs, key)?)
+}
+
+/// Accepts the StartupMessage and answers every query with an empty
+/// result until the client goes away.
+fn serve<S: Read + Write>(stream: &mut S) -> std::io::Result<()> {
+    let mut len = [0; 4];
+    stream.read_exact(&mut len)?;
+    let mut startup = vec![0; u32::from_be_bytes(len) as usize - 4];
+    stream.read_exact(&mut startup)?;
+
+    stream.write_all(b"R\0\0\0\x08\0\0\0\0")?;
+    stream.write_all(b"K\0\0\0\x0c\0\0\0\x2a\0\0\0\x07")?;
+    stream.write_all(b"Z\0\0\0\x05I")?;
+    stream.flush()?;
+
+    loop {
+        let mut header = [0; 5];
+        if stream.read_exact(&mut header).is_err() {
+            return Ok(());
+        }
+        let len = u32::from_be_bytes([header[1], header[2], header[3], header[4]]) as usize;
+        let mut body = vec![0; len - 4];
+        stream.read_exact(&mut body)?;
+
+        match header[0] {
+            b'Q' => {
+                stream.write_all(b"C\0\0\0\x0dSELECT 0\0")?;
+                stream.write_all(b"Z\0\0\0\x05I")?;
+                stream.flush()?;
+            },
+            b'X' => return Ok(()),
+            _ => {},
+        }
+    }
+}
+
+fn query(mut client: Client) -> Result<(), Error> {
+    assert_eq!(client.process_id(), 42);
+    client.execute("SELECT 1;")
+}
+
+#[test]
+fn test_require_accepts_any_certificate() -> Result<(), Error> {
+    let server = FakeServer::start(b'S', fixture!("wrong-name"), false);
+    query(server.client().ssl_mode(SslMode::Require).connect()?)?;
+
+    assert!(!server.join().unwrap());
+    Ok(())
+}
+
+#[test]
+fn test_verify_full() -> Result<(), Error> {
+    let server = FakeServer::start(b'S', fixture!("server"), false);
+    let client = server.client()
+        .ssl_mode(SslMode::VerifyFull)
+        .ssl_root_cert(fixture!("ca.crt"))
+        .connect()?;
+    query(client)?;
+
+    server.join().unwrap();
+    Ok(())
+}
+
+#[test]
+fn test_verify_full_checks_name() -> Result<(), Error> {
+    let server = FakeServer::start(b'S', fixture!("wrong-name"), false);
+    let result = server.client()
+        .ssl_mode(SslMode::VerifyFull)
+        .ssl_root_cert(fixture!("ca.crt"))
+        .connect();
+    assert!(matches!(result, Err(Error::Tls(_))));
+
+    let server = FakeServer::start(b'S', fixture!("wrong-name"), false);
+    let client = server.client()
+        .ssl_mode(SslMode::VerifyCa)
+        .ssl_root_cert(fixture!("ca.crt"))
+        .connect()?;
+    query(client)?;
+    Ok(())
+}
+
+#[test]
+fn test_verify_full_with_hostaddr() -> Result<(), Error> {
+    let server = FakeServer::start(b'S', fixture!("wrong-name"), false);
+    let client = server.client()
+        .host("db.example.com")
+        .hostaddr("127.0.0.1")
+        .ssl_mode(SslMode::VerifyFull)
+        .ssl_root_cert(fixture!("ca.crt"))
+        .connect()?;
+    query(client)?;
+    Ok(())
+}
+
+#[test]
+fn test_untrusted_certificate() {
+    for mode in [SslMode::Require, SslMode::VerifyCa, SslMode::VerifyFull] {
+        let server = FakeServer::start(b'S', fixture!("server"), false);
+        let result = server.client()
+            .ssl_mode(mode)
+            .ssl_root_cert(fixture!("other-ca.crt"))
+            .connect();
+        assert!(matches!(result, Err(Error::Tls(_))), "{:?}", mode);
+    }
+
+    let server = FakeServer::start(b'S', fixture!("server"), false);
+    let result = server.client()
+        .ssl_mode(SslMode::VerifyFull)
+        .connect();
+    assert!(matches!(result, Err(Error::Config(_))));
+}
+
+#[test]
+fn test_client_certificate() -> Result<(), Error> {
+    let server = FakeServer::start(b'S', fixture!("server"), true);
+    let client = server.client()
+        .ssl_mode(SslMode::VerifyFull)
+        .ssl_root_cert(fixture!("ca.crt"))
+        .ssl_client_cert(fixture!("client.crt"), fixture!("client.key"))
+        .connect()?;
+    query(client)?;
+
+    assert!(server.join().unwrap());
+    Ok(())
+}
+
+#[test]
+fn test_server_without_tls() -> Result<(), Error> {
+    let server = FakeServer::start(b'N', fixture!("server"), false);
+    query(server.client().connect()?)?;
+    server.join().unwrap();
+
+    let server = FakeServer::start(b'N', fixture!("server"), false);
+    let result = server.client().ssl_mode(SslMode::Require).connect();
+    assert!(matches!(result, Err(Error::Tls(_))));
+    Ok(())
+}
+
+#[test]
+fn test_read_timeout_with_partial_record() -> Result<(), Error> {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let port = listener.local_addr().unwrap().port() as u32;
+
+    let handle = thread::spawn(move || -> ServerResult {
+        let (mut tcp, _) = listener.accept()?;
+        let mut request = [0; 8];
+        tcp.read_exact(&mut request)?;
+        tcp.write_all(b"S")?;
+
+        let conn = ServerConnection::new(Arc::new(server_config(fixture!("server"), false)?))?;
+        let mut tls = StreamOwned::new(conn, tcp);
+        let mut len = [0; 4];
+        tls.read_exact(&mut len)?;
+        let mut startup = vec![0; u32::from_be_bytes(len) as usize - 4];
+        tls.read_exact(&mut startup)?;
+        tls.write_all(b"R\0\0\0\x08\0\0\0\0K\0\0\0\x0c\0\0\0\x2a\0\0\0\x07Z\0\0\0\x05I")?;
+        tls.flush()?;
+
+        let mut header = [0; 5];
+        tls.read_exact(&mut header)?;
+        let mut body = vec![0; u32::from_be_bytes([header[1], header[2], header[3], header[4]]) as usize - 4];
+        tls.read_exact(&mut body)?;
+
+        // Only the first half of the record holding the answer.
+        tls.conn.writer().write_all(b"C\0\0\0\x0dSELECT 0\0Z\0\0\0\x05I")?;
+        let mut record = Vec::new();
+        tls.conn.write_tls(&mut record)?;
+        tls.sock.write_all(&record[..record.len() / 2])?;
+        thread::sleep(Duration::from_secs(2));
+        Ok(false)
+    });
+
+    let mut client = ClientBuilder::new()
+        .host("127.0.0.1")
+        .port(port)
+        .ssl_mode(SslMode::Require)
+        .read_timeout(Duration::from_millis(300))
+        .cancel_on_timeout(false)
+        .connect()?;
+
+    let started = Instant::now();
+    assert!(matches!(client.execute("SELECT 1;"), Err(Error::Timeout(_))));
+    assert!(started.elapsed() < Duration::from_secs(1));
+    drop(client);
+    handle.join().unwrap().unwrap();
+    Ok(())
+}